@@ -4,16 +4,66 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 #[macro_use]
 mod macros;
+pub mod parser;
+pub mod table_set;
 pub mod tables;
 mod types;
+pub mod validation;
 
 // In SMBIOS structures that contain string values, the strings are located directly after the
 // main structure, and the (byte-valued) index for each string goes in the structure itself.
 type StringIndex = u8;
 
+// Sentinel values for handles to optional structures (e.g. error information)
+pub const HANDLE_NOT_PROVIDED: u16 = 0xfffe;
+pub const HANDLE_NOT_SUPPORTED: u16 = 0xfffd;
+
+// Sentinel value for the Type 4 cache handles when there is no cache at that level
+pub const HANDLE_NO_CACHE: u16 = 0xffff;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SmbiosError {
+    // The formatted section of a structure is too long for its (byte-wide) length field
+    StructureTooLarge,
+    // A structure can't refer to more than `limit` strings
+    TooManyStrings { limit: usize },
+    // Strings must be ASCII and must not contain embedded nulls
+    InvalidString,
+    // An address range which ends before it starts
+    InvalidAddressRange { starting: u64, ending: u64 },
+    // The output buffer can't hold everything that needs to be written
+    BufferTooSmall { needed: usize, available: usize },
+    // A date which is out of range, or isn't in the expected format
+    InvalidDate,
+    // Memory voltages (in millivolts) which don't satisfy min <= configured <= max
+    VoltageRangeInvalid { min: u16, max: u16, configured: u16 },
+    // A processor current speed (in MHz) above its maximum speed
+    SpeedRangeInvalid { max: u16, current: u16 },
+    // Every handle outside the reserved range (0xff00 and up) is already in use
+    HandleSpaceExhausted,
+    // A processor family value which is reserved, e.g. the byte-wide "obtain from Processor
+    // Family 2" indicator
+    InvalidProcessorFamily { family: u16 },
+    // A battery capacity (in mWh) too large to encode, even with the largest multiplier
+    BatteryCapacityTooLarge { capacity_mwh: u32 },
+    // A Type 32 vendor or product specific boot status code outside its range
+    InvalidBootStatusCode { code: u8 },
+    // A Type 3 contained element count range where the minimum is 0xff, the maximum is 0, or
+    // the minimum exceeds the maximum
+    InvalidContainedElementRange { minimum: u8, maximum: u8 },
+    // A Type 15 log access method which doesn't match the constructor used for it
+    InvalidLogAccessMethod { access_method: u8 },
+    // A percentage above 100
+    PercentageInvalid { percent: u64 },
+    // A memory module whose data and ECC widths (in bits) total 0xffff ("unknown") or more
+    ModuleWidthInvalid { data_width: u16, ecc: u16 },
+}
+
 // A generic sink for raw data; used by the `SmbiosTable` trait to serialize structures into.
 pub trait Sink {
     fn byte(&mut self, byte: u8);
@@ -37,6 +87,21 @@ pub trait Sink {
             self.byte(*byte);
         }
     }
+    // The number of bytes written so far, for sinks that track it. Sinks that don't track it
+    // can't be aligned.
+    fn bytes_written(&self) -> usize {
+        0
+    }
+    // Write zero bytes until the write position is a multiple of `alignment`. Alignments of 0
+    // and 1 don't need any padding.
+    fn align_to(&mut self, alignment: usize) {
+        if alignment <= 1 {
+            return;
+        }
+        while !self.bytes_written().is_multiple_of(alignment) {
+            self.byte(0);
+        }
+    }
 }
 
 // It may be useful to have a Vec of u8s be a Sink
@@ -44,9 +109,201 @@ impl Sink for alloc::vec::Vec<u8> {
     fn byte(&mut self, byte: u8) {
         self.push(byte);
     }
+    fn bytes_written(&self) -> usize {
+        self.len()
+    }
+}
+
+// A sink over a fixed-size buffer, for when there's no allocator. Writing past the end of the
+// buffer panics, so check the size first.
+pub struct SliceSink<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> SliceSink<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+}
+
+impl Sink for SliceSink<'_> {
+    fn byte(&mut self, byte: u8) {
+        self.buf[self.pos] = byte;
+        self.pos += 1;
+    }
+    fn bytes_written(&self) -> usize {
+        self.pos
+    }
+}
+
+// A sink over a writer such as a file or socket. Writes are buffered, and the buffer is flushed
+// by `into_inner`. Write errors panic, as `Sink` can't report them.
+#[cfg(feature = "std")]
+pub struct IoSink<W: std::io::Write> {
+    writer: std::io::BufWriter<W>,
+    written: usize,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> IoSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: std::io::BufWriter::new(writer),
+            written: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .map_err(|e| e.into_error())
+            .unwrap()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Sink for IoSink<W> {
+    fn byte(&mut self, byte: u8) {
+        self.vec(&[byte]);
+    }
+    fn vec(&mut self, v: &[u8]) {
+        std::io::Write::write_all(&mut self.writer, v).unwrap();
+        self.written += v.len();
+    }
+    fn bytes_written(&self) -> usize {
+        self.written
+    }
+}
+
+// Allows formatted text to be written to a sink with `write!`
+pub struct SinkWriter<'a>(&'a mut dyn Sink);
+
+impl<'a> SinkWriter<'a> {
+    pub fn new(sink: &'a mut dyn Sink) -> Self {
+        Self(sink)
+    }
+}
+
+impl core::fmt::Write for SinkWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0.vec(s.as_bytes());
+        Ok(())
+    }
 }
 
 // SMBIOS structures can be serialized
 pub trait SmbiosStructure {
     fn serialize(&self, sink: &mut dyn Sink);
+    // The number of bytes `serialize` will write
+    fn byte_length(&self) -> usize;
+    // Serializes the structure when it starts `offset` bytes into the table, for structures that
+    // pad themselves to an alignment
+    fn serialize_at(&self, sink: &mut dyn Sink, _offset: usize) {
+        self.serialize(sink);
+    }
+    // The number of bytes `serialize_at` will write for the same offset
+    fn byte_length_at(&self, _offset: usize) -> usize {
+        self.byte_length()
+    }
+}
+
+// Allows a structure held as a trait object to be used as its concrete type. This is
+// implemented for every `SmbiosTable`, so structures don't implement it themselves.
+pub trait AsAny {
+    fn as_any(&self) -> &dyn core::any::Any;
+    fn as_any_mut(&mut self) -> &mut dyn core::any::Any;
+}
+
+impl<T: SmbiosTable + 'static> AsAny for T {
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn core::any::Any {
+        self
+    }
+}
+
+// Structures that go in the SMBIOS structure table, i.e. everything except the entry point
+pub trait SmbiosTable: SmbiosStructure + AsAny {
+    fn structure_type(&self) -> u8;
+    fn handle(&self) -> u16;
+    // Clone a structure held as a trait object
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable>;
+    // Rewrite the structure's own handle and its references to other structures, passing each
+    // one through `remap`
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16);
+    // The oldest spec version whose entry point can describe a table containing this structure
+    fn minimum_spec_version(&self) -> (u8, u8) {
+        tables::minimum_spec_version(self.structure_type())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_align_to() {
+        let mut output = vec![];
+        output.vec(&[1, 2, 3, 4, 5]);
+        output.align_to(8);
+        output.byte(6);
+        assert_eq!(output, [1, 2, 3, 4, 5, 0, 0, 0, 6]);
+
+        // Already aligned, so nothing is written
+        output.align_to(9);
+        assert_eq!(output.len(), 9);
+        output.align_to(0);
+        output.align_to(1);
+        assert_eq!(output.len(), 9);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_io_sink() {
+        use crate::table_set::SmbiosTableSetBuilder;
+        use crate::tables::*;
+
+        let mut b = BiosInformation::new(0);
+        b.set_vendor("Vendor");
+        let mut m = MemoryDevice::new(2);
+        m.set_locators("DIMM 0", "BANK 0");
+        let mut set = SmbiosTableSetBuilder::new()
+            .add(b)
+            .add(SystemInformation::new(1))
+            .add(m)
+            .build()
+            .unwrap();
+        set.add(EndOfTable::with_padding(3));
+
+        let mut expected = vec![];
+        set.serialize(&mut expected);
+        let mut sink = IoSink::new(std::io::Cursor::new(vec![]));
+        set.serialize(&mut sink);
+        assert_eq!(sink.bytes_written(), set.serialized_size());
+        sink.byte(1);
+        sink.align_to(8);
+        assert_eq!(sink.bytes_written() % 8, 0);
+        let output = sink.into_inner().into_inner();
+        assert_eq!(output[..expected.len()], expected);
+        assert_eq!(output.len(), (expected.len() + 1).next_multiple_of(8));
+    }
+
+    #[test]
+    fn test_sink_writer() {
+        let mut output = vec![];
+        let vendor = "Acme";
+        write!(
+            SinkWriter::new(&mut output),
+            "BIOS vendor: {vendor}, {:#x}",
+            42
+        )
+        .unwrap();
+        output.byte(0);
+        assert_eq!(output, b"BIOS vendor: Acme, 0x2a\0");
+    }
 }
@@ -5,20 +5,53 @@
 //! integer data types and strings, then it can be defined using `simple_smbios_structure`. The
 //! SMBIOS header (4 bytes) is automatically prepended to each such structure. The macro
 //! searches for `StringIndex` types and adds a setter function which accepts a string and adds
-//! it to the list of strings for that structure. Other data types come with generic
-//! setters. `SmbiosStructure` will be implemented for the structure.
+//! it to the list of strings for that structure, along with functions to read it, to clear it or
+//! to set it only if the string isn't empty. Other data types come with generic setters. `SmbiosStructure`
+//! will be implemented for the structure.
+//!
+//! A field declared as `name: [EntryType; variable]` after the data struct is an array with a
+//! runtime-variable count, which follows the fixed section. It's held in a `Vec`, with an
+//! `add_name` function which appends an entry and updates the length in the header, or returns
+//! `SmbiosError::StructureTooLarge` if the length can't cover another entry.
+//!
+//! Fields of type `StructureHandle` refer to other structures, and are rewritten along with the
+//! structure's own handle by `SmbiosTable::remap_handles`. Entry types of variable arrays must
+//! provide a `remap_handles` function for the same purpose.
 
 #[macro_export]
 macro_rules! inner_impl {
     // Dump everything out
-    (@munch () -> {impl $name:ident $innername:ident $($output:tt)*}) => {
+    (@munch () -> {impl $name:ident $innername:ident $($output:tt)*} [$($sident:ident)*] [$($hident:ident)*]) => {
         impl $name {
             $($output)*
 
+            // Rewrite the structure's own handle and each field which refers to another
+            // structure
+            fn remap_data_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+                self.data.handle = remap(self.data.handle.get()).into();
+                $(
+                    self.data.$hident = remap(self.data.$hident.get()).into();
+                )*
+            }
+
+            // Remove a string, and renumber the fields that refer to the strings after it
+            #[allow(dead_code)]
+            fn remove_string(&mut self, index: StringIndex) {
+                if index == 0 {
+                    return;
+                }
+                self.strings.remove(usize::from(index) - 1);
+                $(
+                    if self.data.$sident > index {
+                        self.data.$sident -= 1;
+                    }
+                )*
+            }
+
             pub fn new(handle: u16) -> Self {
                 Self {
                     data: $innername::new(handle),
-                    strings: Vec::new(),
+                    ..Default::default()
                 }
             }
 
@@ -28,20 +61,58 @@ macro_rules! inner_impl {
         }
     };
 
-    // Create a special setter for each StringIndex
-    (@munch ($ident:ident : StringIndex, $($next:tt)*) -> {$($output:tt)*}) => {
+    // Create a special setter for each StringIndex, which replaces any existing string, and a
+    // function to clear it. An index of 0 means the string isn't present.
+    (@munch ($ident:ident : StringIndex, $($next:tt)*) -> {$($output:tt)*} [$($sident:ident)*] [$($hident:ident)*]) => {
         inner_impl!(@munch ($($next)*) -> {
             $($output)*
                 paste! {
                     pub fn [<set_ $ident>](&mut self, s: &str) {
-                        self.data.$ident = self.add_string(s);
+                        match self.data.$ident {
+                            0 => self.data.$ident = self.add_string(s),
+                            i => self.strings[usize::from(i) - 1] = s.into(),
+                        }
+                    }
+
+                    // An absent string reads as an empty one
+                    pub fn [<get_ $ident>](&self) -> &str {
+                        self.get_string(self.data.$ident)
+                    }
+
+                    pub fn [<clear_ $ident>](&mut self) {
+                        let index = self.data.$ident;
+                        self.data.$ident = 0;
+                        self.remove_string(index);
+                    }
+
+                    // An empty string is stored as no string (index 0), rather than as an
+                    // empty string
+                    pub fn [<set_ $ident _or_empty>](&mut self, s: &str) {
+                        if s.is_empty() {
+                            self.[<clear_ $ident>]();
+                        } else {
+                            self.[<set_ $ident>](s);
+                        }
                     }
                 }
-        });
+        } [$($sident)* $ident] [$($hident)*]);
+    };
+
+    // Handles of other structures get a generic setter, and are rewritten when the structure is
+    // moved into a table set with different handles
+    (@munch ($ident:ident : StructureHandle, $($next:tt)*) -> {$($output:tt)*} [$($sident:ident)*] [$($hident:ident)*]) => {
+        inner_impl!(@munch ($($next)*) -> {
+            $($output)*
+                paste! {
+                    pub fn [<set_ $ident>](&mut self, t: StructureHandle) {
+                        self.data.$ident = t;
+                    }
+                }
+        } [$($sident)*] [$($hident)* $ident]);
     };
 
     // Create a generic setter for all other types
-    (@munch ($ident:ident : $ty:ty, $($next:tt)*) -> {$($output:tt)*}) => {
+    (@munch ($ident:ident : $ty:ty, $($next:tt)*) -> {$($output:tt)*} [$($sident:ident)*] [$($hident:ident)*]) => {
         inner_impl!(@munch ($($next)*) -> {
             $($output)*
                 paste! {
@@ -49,12 +120,12 @@ macro_rules! inner_impl {
                         self.data.$ident = t;
                     }
                 }
-        });
+        } [$($sident)*] [$($hident)*]);
     };
 
     // Initial match
     (impl $name:ident $innername:ident { $($input:tt)* }) => {
-        inner_impl!(@munch ($($input)*) -> {impl $name $innername});
+        inner_impl!(@munch ($($input)*) -> {impl $name $innername} [] []);
     }
 }
 
@@ -103,14 +174,15 @@ macro_rules! inner_new {
 #[macro_export]
 macro_rules! simple_smbios_structure {
     // No more input to consume, so the final output is dumped
-    (@outer () -> {struct $name:ident $(($id:ident: $ty:ty))*}) => {
-        #[derive(Debug, Default)]
+    (@outer () -> {struct $name:ident $(($id:ident: $ty:ty))*} [$(($vid:ident: $vty:ty))*]) => {
+        #[derive(Clone, Debug, Default)]
         pub struct $name {
-            $($id: $ty),*,
+            $($id: $ty,)*
+            $($vid: Vec<$vty>,)*
             strings: Vec<String>,
         }
 
-        serialize_structure_with_strings!{$name}
+        serialize_structure_with_strings!{$name $(, $vid: $vty)*}
 
         impl $name {
             #[allow(dead_code)]
@@ -118,34 +190,86 @@ macro_rules! simple_smbios_structure {
                 self.strings.push(s.into());
                 self.strings.len().try_into().unwrap()
             }
+
+            #[allow(dead_code)]
+            fn get_string(&self, index: u8) -> &str {
+                match index {
+                    0 => "",
+                    i => &self.strings[i as usize - 1],
+                }
+            }
+
+            // The length in the header covers the fixed section and the variable arrays
+            #[allow(dead_code)]
+            fn update_length(&mut self) {
+                self.data.length = self.formatted_length().try_into().unwrap();
+            }
+
+            #[allow(dead_code)]
+            fn formatted_length(&self) -> usize {
+                self.data.as_bytes().len() $(+ self.$vid.len() * core::mem::size_of::<$vty>())*
+            }
+
+            // The entry isn't added if the length byte can't cover it
+            $(
+                paste! {
+                    pub fn [<add_ $vid>](&mut self, entry: $vty) -> Result<(), SmbiosError> {
+                        if self.formatted_length() + core::mem::size_of::<$vty>() > 0xff {
+                            return Err(SmbiosError::StructureTooLarge);
+                        }
+                        self.$vid.push(entry);
+                        self.update_length();
+                        Ok(())
+                    }
+                }
+            )*
         }
     };
 
+    // Skip the separator after an inner struct definition
+    (@outer (, $($next:tt)*) -> {$($output:tt)*} [$($var:tt)*]) => {
+        simple_smbios_structure!(@outer ($($next)*) -> {$($output)*} [$($var)*]);
+    };
+
     // Found a struct definition inside, break it into a separate call
-    (@outer ($id:ident: struct $name:ident {$($inner:tt)*} $($next:tt)*) -> {struct $outername:ident $n:expr, $($output:tt)*}) => {
+    (@outer ($id:ident: struct $name:ident {$($inner:tt)*} $($next:tt)*) -> {struct $outername:ident $n:expr, $($output:tt)*} [$($var:tt)*]) => {
         inner_struct!(struct $name { $($inner)* });
         inner_impl!(impl $outername $name { $($inner)* });
         inner_new!($name, $n);
-        simple_smbios_structure!(@outer ($($next)*) -> {struct $outername $($output)* ($id: $name)});
+        impl $outername {
+            pub const TYPE_NUMBER: u8 = $n;
+        }
+        simple_smbios_structure!(@outer ($($next)*) -> {struct $outername $($output)* ($id: $name)} [$($var)*]);
+    };
+
+    // A variable-length array of entries follows the fixed section, so it's kept out of the
+    // data struct and serialized separately
+    (@outer ($id:ident : [$ty:ty; variable], $($next:tt)*) -> {$($output:tt)*} [$($var:tt)*]) => {
+        simple_smbios_structure!(@outer ($($next)*) -> {$($output)*} [$($var)* ($id: $ty)]);
     };
 
     // Handle other fields
-    (@outer ($id:ident : $ty:ty, $($next:tt)*) -> {$($output:tt)*}) => {
-        simple_smbios_structure!(@outer ($($next)*) -> {$($output)* ($id: $ty)});
+    (@outer ($id:ident : $ty:ty, $($next:tt)*) -> {$($output:tt)*} [$($var:tt)*]) => {
+        simple_smbios_structure!(@outer ($($next)*) -> {$($output)* ($id: $ty)} [$($var)*]);
     };
 
     // Initial expansion
     ($n:expr, struct $name:ident { $($input:tt)*} ) => {
-        simple_smbios_structure!(@outer ($($input)*) -> {struct $name $n,});
+        simple_smbios_structure!(@outer ($($input)*) -> {struct $name $n,} []);
     }
 }
 
 #[macro_export]
 macro_rules! serialize_structure_with_strings {
-    ($x:ty) => {
+    ($x:ty $(, $vid:ident: $vty:ty)*) => {
         impl SmbiosStructure for $x {
             fn serialize(&self, sink: &mut dyn Sink) {
                 sink.vec(self.data.as_bytes());
+                $(
+                    for entry in &self.$vid {
+                        sink.vec(entry.as_bytes());
+                    }
+                )*
                 for s in &self.strings {
                     sink.vec(s.as_bytes());
                     sink.byte(0);
@@ -155,6 +279,38 @@ macro_rules! serialize_structure_with_strings {
                     sink.byte(0);
                 }
             }
+
+            fn byte_length(&self) -> usize {
+                let strings: usize = self.strings.iter().map(|s| s.len() + 1).sum();
+                let entries = 0 $(+ self.$vid.len() * core::mem::size_of::<$vty>())*;
+                self.data.as_bytes().len()
+                    + entries
+                    + strings
+                    + if self.strings.is_empty() { 2 } else { 1 }
+            }
+        }
+
+        impl SmbiosTable for $x {
+            fn structure_type(&self) -> u8 {
+                self.data.r#type
+            }
+
+            fn handle(&self) -> u16 {
+                self.data.handle.get()
+            }
+
+            fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+                alloc::boxed::Box::new(self.clone())
+            }
+
+            fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+                self.remap_data_handles(remap);
+                $(
+                    for entry in &mut self.$vid {
+                        entry.remap_handles(remap);
+                    }
+                )*
+            }
         }
     };
 }
@@ -0,0 +1,507 @@
+// Copyright 2024 Rivos, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for walking serialized SMBIOS structures, without fully parsing them.
+
+use crate::tables::{EntryPoint, EntryPoint2};
+use crate::{Sink, SmbiosStructure, SmbiosTable};
+use alloc::vec::Vec;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    // The length byte is smaller than the 4-byte header
+    LengthTooShort,
+    // The data ends before the structure's fixed section or string section does
+    Truncated,
+    // There's more data after a structure which should be on its own
+    TrailingData,
+}
+
+// A single structure within a serialized table area
+#[derive(Copy, Clone, Debug)]
+pub struct StructureHeader<'a> {
+    pub ty: u8,
+    pub length: u8,
+    pub handle: u16,
+    // The fixed section after the header, not including strings
+    pub body: &'a [u8],
+    // The entire structure, including the header and string section
+    raw: &'a [u8],
+}
+
+impl<'a> StructureHeader<'a> {
+    pub fn raw(&self) -> &'a [u8] {
+        self.raw
+    }
+
+    // The strings and their terminators, without the header, fixed section or final double null
+    fn string_section(&self) -> &'a [u8] {
+        &self.raw[4 + self.body.len()..self.raw.len() - 2]
+    }
+
+    // The strings in the string section, in order. Strings which aren't valid UTF-8 are returned
+    // as empty, so that the rest keep their positions.
+    pub fn strings(&self) -> Vec<&'a str> {
+        let section = self.string_section();
+        if section.is_empty() {
+            return Vec::new();
+        }
+        section
+            .split(|b| *b == 0)
+            .map(|s| core::str::from_utf8(s).unwrap_or(""))
+            .collect()
+    }
+
+    // Strings are numbered from 1, with 0 meaning there's no string
+    pub fn get_string(&self, index: u8) -> Option<&'a str> {
+        let section = self.string_section();
+        if index == 0 || section.is_empty() {
+            return None;
+        }
+        let s = section.split(|b| *b == 0).nth(usize::from(index) - 1)?;
+        core::str::from_utf8(s).ok()
+    }
+}
+
+pub struct SimpleStructureIterator<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SimpleStructureIterator<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn parse_next(&self) -> Result<StructureHeader<'a>, ParseError> {
+        if self.data.len() < 4 {
+            return Err(ParseError::Truncated);
+        }
+        let length = self.data[1];
+        if length < 4 {
+            return Err(ParseError::LengthTooShort);
+        }
+        let length_usize = usize::from(length);
+        if self.data.len() < length_usize {
+            return Err(ParseError::Truncated);
+        }
+
+        // The string section ends with a double null
+        let strings = &self.data[length_usize..];
+        let strings_len = strings
+            .windows(2)
+            .position(|w| w == [0, 0])
+            .ok_or(ParseError::Truncated)?
+            + 2;
+
+        Ok(StructureHeader {
+            ty: self.data[0],
+            length,
+            handle: u16::from_le_bytes([self.data[2], self.data[3]]),
+            body: &self.data[4..length_usize],
+            raw: &self.data[..length_usize + strings_len],
+        })
+    }
+}
+
+impl<'a> Iterator for SimpleStructureIterator<'a> {
+    type Item = Result<StructureHeader<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        match self.parse_next() {
+            Ok(header) => {
+                self.data = &self.data[header.raw.len()..];
+                Some(Ok(header))
+            }
+            Err(e) => {
+                // There's no way to find the next structure, so stop here
+                self.data = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+// A structure read from an existing table, which is re-emitted verbatim: the entire structure
+// including the header and string section. The fixed section can be edited in place, but the
+// header and strings can't, so the structure stays well-formed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawSmbiosStructure(Vec<u8>);
+
+impl RawSmbiosStructure {
+    // `bytes` must hold exactly one structure
+    pub fn new(bytes: Vec<u8>) -> Result<Self, ParseError> {
+        let header = SimpleStructureIterator::new(&bytes)
+            .next()
+            .ok_or(ParseError::Truncated)??;
+        if header.raw.len() != bytes.len() {
+            return Err(ParseError::TrailingData);
+        }
+        Ok(Self(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    // The fixed section after the header, for updating fields by their offset from the start of
+    // the structure less 4
+    pub fn body_mut(&mut self) -> &mut [u8] {
+        let length = usize::from(self.0[1]);
+        &mut self.0[4..length]
+    }
+
+    pub fn set_handle(&mut self, handle: u16) {
+        self.0[2..4].copy_from_slice(&handle.to_le_bytes());
+    }
+}
+
+impl From<StructureHeader<'_>> for RawSmbiosStructure {
+    fn from(header: StructureHeader) -> Self {
+        Self(header.raw.into())
+    }
+}
+
+impl SmbiosStructure for RawSmbiosStructure {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        sink.vec(&self.0);
+    }
+
+    fn byte_length(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl SmbiosTable for RawSmbiosStructure {
+    fn structure_type(&self) -> u8 {
+        self.0[0]
+    }
+
+    fn handle(&self) -> u16 {
+        u16::from_le_bytes([self.0[2], self.0[3]])
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        for offset in core::iter::once(2).chain(handle_reference_offsets(&self.0)) {
+            let handle = u16::from_le_bytes([self.0[offset], self.0[offset + 1]]);
+            self.0[offset..offset + 2].copy_from_slice(&remap(handle).to_le_bytes());
+        }
+    }
+}
+
+// Offsets of the fields in a serialized structure which hold handles of other structures
+fn handle_reference_offsets(raw: &[u8]) -> Vec<usize> {
+    let length = usize::from(raw[1]);
+    // A count of items at `count_offset`, each `stride` bytes, with a handle `handle_offset`
+    // bytes into each item
+    let list = |count_offset: usize, first_item: usize, stride: usize, handle_offset: usize| {
+        let count = raw.get(count_offset).map_or(0, |n| usize::from(*n));
+        (0..count).map(move |i| first_item + i * stride + handle_offset)
+    };
+    let offsets: Vec<usize> = match raw[0] {
+        2 => core::iter::once(0xb).chain(list(0xe, 0xf, 2, 0)).collect(),
+        4 => [0x1a, 0x1c, 0x1e].into(),
+        5 => list(0xe, 0xf, 2, 0).collect(),
+        14 => (0..length.saturating_sub(5) / 3)
+            .map(|i| 6 + i * 3)
+            .collect(),
+        16 => [0xb].into(),
+        17 => [0x4, 0x6].into(),
+        19 => [0xc].into(),
+        20 => [0xc, 0xe].into(),
+        27 => [0x4].into(),
+        35 => [0x5, 0x7, 0x9].into(),
+        37 => list(0x6, 0x7, 3, 1).collect(),
+        39 => [0x10, 0x12, 0x14].into(),
+        40 => {
+            // Entries are variable length, with the length in their first byte
+            let mut offsets = Vec::new();
+            let mut entry = 5;
+            for _ in 0..raw.get(4).copied().unwrap_or(0) {
+                offsets.push(entry + 1);
+                entry += usize::from(raw.get(entry).copied().unwrap_or(0)).max(1);
+            }
+            offsets
+        }
+        44 => [0x4].into(),
+        45 => list(0x17, 0x18, 2, 0).collect(),
+        46 => [0x7].into(),
+        _ => Vec::new(),
+    };
+    // Fields past the end of the fixed section belong to older, shorter versions of the structure
+    offsets.into_iter().filter(|o| o + 2 <= length).collect()
+}
+
+// Entry points are always on a 16-byte boundary
+const ENTRY_POINT_ALIGNMENT: usize = 16;
+
+// Searches memory, such as a firmware image or the legacy 0xf0000 region, for an entry point
+pub struct SmbiosParser;
+
+impl SmbiosParser {
+    // Returns the offset of the first valid 64-bit entry point in `haystack`
+    pub fn scan_for_entry_point_3(haystack: &[u8]) -> Option<(usize, EntryPoint)> {
+        Self::scan(haystack, EntryPoint::from_bytes)
+    }
+
+    // Returns the offset of the first valid 32-bit entry point in `haystack`
+    pub fn scan_for_entry_point_2(haystack: &[u8]) -> Option<(usize, EntryPoint2)> {
+        Self::scan(haystack, EntryPoint2::from_bytes)
+    }
+
+    fn scan<T>(haystack: &[u8], parse: fn(&[u8]) -> Option<T>) -> Option<(usize, T)> {
+        (0..haystack.len())
+            .step_by(ENTRY_POINT_ALIGNMENT)
+            .find_map(|offset| parse(&haystack[offset..]).map(|e| (offset, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::*;
+    use crate::SmbiosStructure;
+    use alloc::boxed::Box;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_strings() {
+        let mut output = vec![];
+        let mut b = BiosInformation::new(1);
+        b.set_vendor("Vendor");
+        b.set_bios_version("1.0");
+        b.serialize(&mut output);
+        SystemInformation::new(2).serialize(&mut output);
+
+        let mut it = SimpleStructureIterator::new(&output);
+        let header = it.next().unwrap().unwrap();
+        assert_eq!(header.strings(), ["Vendor", "1.0"]);
+        assert_eq!(header.get_string(header.body[0]), Some("Vendor"));
+        assert_eq!(header.get_string(1), Some("Vendor"));
+        assert_eq!(header.get_string(2), Some("1.0"));
+        assert_eq!(header.get_string(0), None);
+        assert_eq!(header.get_string(3), None);
+
+        let header = it.next().unwrap().unwrap();
+        assert!(header.strings().is_empty());
+        assert_eq!(header.get_string(1), None);
+    }
+
+    #[test]
+    fn test_scan_for_entry_point() {
+        let mut haystack = vec![0u8; 0x100];
+        let mut output = vec![];
+        EntryPoint::new(0x1234, 0x8000_0000).serialize(&mut output);
+        haystack[0x30..0x48].copy_from_slice(&output);
+        // A copy which isn't aligned, and a copy with a bad checksum
+        haystack[0x88..0xa0].copy_from_slice(&output);
+        haystack[0x10..0x28].copy_from_slice(&output);
+        haystack[0x15] ^= 1;
+
+        let (offset, entry_point) = SmbiosParser::scan_for_entry_point_3(&haystack).unwrap();
+        assert_eq!(offset, 0x30);
+        assert_eq!(entry_point.get_structure_max_size(), 0x1234);
+        assert_eq!(entry_point.get_table_address(), 0x8000_0000);
+        assert!(SmbiosParser::scan_for_entry_point_2(&haystack).is_none());
+
+        let mut output = vec![];
+        EntryPoint2::new(0x80, 0x400, 0xe_0000, 12).serialize(&mut output);
+        assert_eq!(output.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)), 0);
+        haystack[0xc0..0xdf].copy_from_slice(&output);
+        let (offset, entry_point) = SmbiosParser::scan_for_entry_point_2(&haystack).unwrap();
+        assert_eq!(offset, 0xc0);
+        assert_eq!(entry_point.get_table_length(), 0x400);
+        assert_eq!(entry_point.get_table_address(), 0xe_0000);
+
+        // The intermediate checksum is checked too, even when the overall checksum is good
+        haystack[0xd5] = haystack[0xd5].wrapping_add(1);
+        haystack[0xc4] = haystack[0xc4].wrapping_sub(1);
+        assert!(SmbiosParser::scan_for_entry_point_2(&haystack).is_none());
+
+        // An entry point which runs off the end of the haystack isn't found
+        assert!(SmbiosParser::scan_for_entry_point_3(&haystack[..0x40]).is_none());
+    }
+
+    #[test]
+    fn test_iterator() {
+        let mut output = vec![];
+        let mut b = BiosInformation::new(1);
+        b.set_vendor("Vendor");
+        b.set_bios_version("1.0");
+        b.serialize(&mut output);
+        let mut o = OemStrings::new(2);
+        o.add_string("OEM");
+        o.serialize(&mut output);
+        PhysicalMemoryArray::new(3).serialize(&mut output);
+        EndOfTable::new(4).serialize(&mut output);
+
+        let mut it = SimpleStructureIterator::new(&output);
+        let s = it.next().unwrap().unwrap();
+        assert_eq!((s.ty, s.length, s.handle), (0, 0x14, 1));
+        assert_eq!(s.body.len(), 0x10);
+        assert_eq!(s.raw().len(), 0x14 + 12);
+        let s = it.next().unwrap().unwrap();
+        assert_eq!((s.ty, s.length, s.handle), (11, 5, 2));
+        assert_eq!(s.body, &[1]);
+        let s = it.next().unwrap().unwrap();
+        assert_eq!((s.ty, s.length, s.handle), (16, 0x17, 3));
+        let s = it.next().unwrap().unwrap();
+        assert_eq!((s.ty, s.length, s.handle), (127, 4, 4));
+        assert!(s.body.is_empty());
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_iterator_malformed() {
+        let mut it = SimpleStructureIterator::new(&[1, 2, 0, 0, 0, 0]);
+        assert_eq!(it.next().unwrap().unwrap_err(), ParseError::LengthTooShort);
+        assert!(it.next().is_none());
+
+        let mut it = SimpleStructureIterator::new(&[127, 4, 0, 0, b'a', 0]);
+        assert_eq!(it.next().unwrap().unwrap_err(), ParseError::Truncated);
+
+        let mut it = SimpleStructureIterator::new(&[127, 8, 0, 0, 0, 0]);
+        assert_eq!(it.next().unwrap().unwrap_err(), ParseError::Truncated);
+    }
+
+    #[test]
+    fn test_raw_structure() {
+        assert_eq!(RawSmbiosStructure::new(vec![]), Err(ParseError::Truncated));
+        assert_eq!(
+            RawSmbiosStructure::new(vec![1, 2, 0, 0, 0, 0]),
+            Err(ParseError::LengthTooShort)
+        );
+        assert_eq!(
+            RawSmbiosStructure::new(vec![127, 4, 0, 0, 0, 0, 0]),
+            Err(ParseError::TrailingData)
+        );
+
+        let mut output = vec![];
+        let mut b = BiosInformation::new(1);
+        b.set_vendor("Vendor");
+        b.serialize(&mut output);
+        let mut raw = RawSmbiosStructure::new(output.clone()).unwrap();
+        assert_eq!((raw.structure_type(), raw.handle()), (0, 1));
+
+        // Update the BIOS ROM size (offset 9) and the handle
+        raw.body_mut()[9 - 4] = 0xf;
+        raw.set_handle(0x20);
+        output[9] = 0xf;
+        output[2] = 0x20;
+        assert_eq!(raw.as_bytes(), output.as_slice());
+        assert_eq!(raw.handle(), 0x20);
+    }
+
+    // Every structure with handle fields needs an entry in `handle_reference_offsets`, so that
+    // the same structure is remapped the same way whether it was built or parsed
+    #[test]
+    fn test_raw_remap_matches_typed() {
+        let mut group = GroupAssociations::new(14);
+        group.add_item(GroupAssociationItem::new(4, 0x40)).unwrap();
+        group.add_item(GroupAssociationItem::new(7, 0x70)).unwrap();
+        let mut channel = MemoryChannel::new(37);
+        channel.add_device(1, 0x1100).unwrap();
+        channel.add_device(2, 0x1101).unwrap();
+        let mut info = AdditionalInformation::new(40);
+        info.add_entry(4, 0x14, &[0x42], "OC").unwrap();
+        info.add_entry(7, 0x08, &[0xaa, 0xbb], "ID").unwrap();
+        let mut oem = OemStrings::new(11);
+        oem.add_string("OEM");
+        let mut inventory = FirmwareInventoryInformation::new(45);
+        inventory.add_associated_component(0x20).unwrap();
+        inventory.add_associated_component(0x21).unwrap();
+        let tables: Vec<Box<dyn SmbiosTable>> = vec![
+            Box::new(BiosInformation::new(0)),
+            Box::new(SystemInformation::new(1)),
+            Box::new(SystemEnclosure::new(3)),
+            Box::new(ProcessorInformation::new(4)),
+            Box::new(CacheInformation::new(7)),
+            Box::new(SystemSlots::new(9)),
+            Box::new(oem),
+            Box::new(group),
+            Box::new(SystemEventLog::new_memory_mapped(15, 0)),
+            Box::new(PhysicalMemoryArray::new(16)),
+            Box::new(MemoryDevice::new(17)),
+            Box::new(MemoryArrayMappedAddress::new(19)),
+            Box::new(MemoryDeviceMappedAddress::new(20)),
+            Box::new(PortableBattery::new(22)),
+            Box::new(VoltageProbe::new(26)),
+            Box::new(TemperatureProbe::new(28)),
+            Box::new(CurrentProbe::new(29)),
+            Box::new(OwnedSystemBootInformation::new(32, Default::default()).unwrap()),
+            Box::new(channel),
+            Box::new(info),
+            Box::new(TpmDevice::new(43)),
+            Box::new(RiscvProcessorAdditionalInformation::new(44)),
+            Box::new(LoongArchProcessorAdditionalInformation::new(44)),
+            Box::new(inventory),
+            Box::new(EndOfTable::new(127)),
+        ];
+        #[cfg(feature = "legacy_types")]
+        let tables = {
+            let mut tables = tables;
+            let mut controller = MemoryControllerInformation::new(5);
+            controller.add_memory_module_handle(6);
+            controller.add_memory_module_handle(7);
+            tables.push(Box::new(controller));
+            tables.push(Box::new(MemoryModuleInformation::new(6)));
+            tables.push(Box::new(OnBoardDevicesInformation::new(10)));
+            tables
+        };
+
+        // Every handle changes, including the zeros left in unset handle fields
+        let remap = |handle: u16| handle ^ 0x5a5a;
+        for mut table in tables {
+            let mut output = vec![];
+            table.serialize(&mut output);
+            let mut raw = RawSmbiosStructure::new(output).unwrap();
+            table.remap_handles(&remap);
+            raw.remap_handles(&remap);
+
+            let mut output = vec![];
+            table.serialize(&mut output);
+            assert_eq!(
+                raw.as_bytes(),
+                output.as_slice(),
+                "type {}",
+                table.structure_type()
+            );
+        }
+    }
+
+    #[test]
+    fn test_type_numbers() {
+        let mut output = vec![];
+        BiosInformation::new(0).serialize(&mut output);
+        SystemInformation::new(1).serialize(&mut output);
+        let mut o = OemStrings::new(2);
+        o.add_string("OEM");
+        o.serialize(&mut output);
+        MemoryDevice::new(3).serialize(&mut output);
+        MemoryChannel::new(4).serialize(&mut output);
+        EndOfTable::new(5).serialize(&mut output);
+
+        let types: Vec<u8> = SimpleStructureIterator::new(&output)
+            .map(|h| h.unwrap().ty)
+            .collect();
+        assert_eq!(
+            types,
+            [
+                BiosInformation::TYPE_NUMBER,
+                SystemInformation::TYPE_NUMBER,
+                OemStrings::TYPE_NUMBER,
+                MemoryDevice::TYPE_NUMBER,
+                MemoryChannel::TYPE_NUMBER,
+                EndOfTable::TYPE_NUMBER,
+            ]
+        );
+        assert_eq!(EndOfTable::TYPE_NUMBER, 127);
+    }
+}
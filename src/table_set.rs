@@ -0,0 +1,1497 @@
+// Copyright 2024 Rivos, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A collection of SMBIOS structures which together form the structure table.
+
+use crate::parser::{ParseError, RawSmbiosStructure, SimpleStructureIterator};
+use crate::tables::{
+    EndOfTable, EntryPoint, MemoryArrayHandle, MemoryArrayMappedAddress, MemoryDevice,
+    MemoryDeviceMappedAddress, PhysicalMemoryArray, ProcessorInformation, SMBIOS_VERSION,
+};
+use crate::{Sink, SliceSink, SmbiosError, SmbiosStructure, SmbiosTable};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::mem;
+
+// Types the spec requires to be present, in its recommended order
+pub const REQUIRED_TYPES: [u8; 10] = [0, 1, 3, 4, 7, 9, 17, 19, 32, 127];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LayoutProblem {
+    // The type byte doesn't match `structure_type()`
+    TypeMismatch {
+        structure_type: u8,
+        type_byte: u8,
+    },
+    // The length byte doesn't mark the start of a string section which runs to the end of the
+    // structure
+    LengthMismatch {
+        length_byte: u8,
+    },
+    // The structure doesn't end with a double null
+    MissingTerminator,
+    // The number of bytes written doesn't match `byte_length()`
+    ByteLengthMismatch {
+        byte_length: usize,
+        serialized: usize,
+    },
+}
+
+// A problem with the serialized form of the structure with the given handle
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LayoutError {
+    pub handle: u16,
+    pub problem: LayoutProblem,
+}
+
+// A summary of the sizes and contents of a table set
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SmbiosStatistics {
+    pub total_serialized_bytes: usize,
+    pub structure_count: usize,
+    // The structure table maximum size given to the entry point, which is the size of the table
+    pub max_structure_bytes: usize,
+    pub type_counts: BTreeMap<u8, usize>,
+    // The string sections, including their terminators
+    pub string_bytes: usize,
+    // The headers and fixed sections, as given by the length bytes
+    pub fixed_section_bytes: usize,
+}
+
+// A structure which needs a newer spec version than the one this crate implements
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SpecWarning {
+    pub handle: u16,
+    pub structure_type: u8,
+    pub required: (u8, u8),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MergeError {
+    // There aren't enough unused handles to give every merged structure its own
+    HandleSpaceExhausted,
+}
+
+// Handles from 0xff00 up are reserved
+const MAX_HANDLE: u16 = 0xfeff;
+
+// A structure in a set, along with its type and handle
+#[derive(Copy, Clone)]
+pub struct StructureEntry<'a> {
+    pub ty: u8,
+    pub handle: u16,
+    pub structure: &'a dyn SmbiosTable,
+}
+
+#[derive(Default)]
+pub struct SmbiosTableSet {
+    tables: Vec<Box<dyn SmbiosTable>>,
+}
+
+impl SmbiosTableSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, table: impl SmbiosTable + 'static) {
+        self.tables.push(Box::new(table));
+    }
+
+    // Reads an existing structure table, up to and including the End-of-Table structure, so
+    // that structures can be added, removed or reordered and the table written out again
+    pub fn from_raw_table_area(data: &[u8]) -> Result<Self, ParseError> {
+        let mut set = Self::new();
+        for header in SimpleStructureIterator::new(data) {
+            let header = header?;
+            set.add(RawSmbiosStructure::from(header));
+            if header.ty == EndOfTable::TYPE_NUMBER {
+                break;
+            }
+        }
+        Ok(set)
+    }
+
+    // Combines two sets, such as ones built for separate sockets. Handles in `other` which are
+    // already used in `self` are given unused ones, and references to them from other structures
+    // in `other` are updated to match. Structures keep their types. Only one End-of-Table
+    // structure is kept, and it goes last.
+    pub fn merge(self, other: SmbiosTableSet) -> Result<SmbiosTableSet, MergeError> {
+        let used: BTreeSet<u16> = self
+            .iter()
+            .chain(other.iter())
+            .map(|t| t.handle())
+            .collect();
+        let start = used
+            .last()
+            .map_or(0, |h| h.saturating_add(1).min(MAX_HANDLE + 1));
+        let mut free = (start..=MAX_HANDLE)
+            .chain(0..start)
+            .filter(|h| !used.contains(h));
+
+        let mut remap = BTreeMap::new();
+        for t in other.iter() {
+            let handle = t.handle();
+            if self.iter().any(|s| s.handle() == handle) && !remap.contains_key(&handle) {
+                let new_handle = free.next().ok_or(MergeError::HandleSpaceExhausted)?;
+                remap.insert(handle, new_handle);
+            }
+        }
+
+        let (mut tables, mut ends): (Vec<_>, Vec<_>) = self
+            .tables
+            .into_iter()
+            .partition(|t| t.structure_type() != EndOfTable::TYPE_NUMBER);
+        for t in other.tables {
+            if t.structure_type() == EndOfTable::TYPE_NUMBER {
+                if ends.is_empty() {
+                    ends.push(relocate_handles(t, &remap));
+                }
+            } else {
+                tables.push(relocate_handles(t, &remap));
+            }
+        }
+        tables.extend(ends);
+        Ok(SmbiosTableSet { tables })
+    }
+
+    // Returns the index the structure was inserted at
+    fn insert_before_end_of_table(&mut self, table: Box<dyn SmbiosTable>) -> usize {
+        let index = self
+            .tables
+            .iter()
+            .position(|t| t.structure_type() == EndOfTable::TYPE_NUMBER)
+            .unwrap_or(self.tables.len());
+        self.tables.insert(index, table);
+        index
+    }
+
+    // The handle after the highest one in use, or if that would be reserved, the lowest unused
+    // handle
+    pub fn next_free_handle(&self) -> Result<u16, SmbiosError> {
+        let used: BTreeSet<u16> = self.iter().map(|t| t.handle()).collect();
+        match used.last() {
+            None => Ok(0),
+            Some(h) if *h < MAX_HANDLE => Ok(h + 1),
+            Some(_) => (0..=MAX_HANDLE)
+                .find(|h| !used.contains(h))
+                .ok_or(SmbiosError::HandleSpaceExhausted),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn SmbiosTable> {
+        self.tables.iter().map(|t| t.as_ref())
+    }
+
+    // The structures in the order they were added, which is the order they're serialized in
+    pub fn entries(&self) -> impl Iterator<Item = StructureEntry<'_>> {
+        self.iter().map(|t| StructureEntry {
+            ty: t.structure_type(),
+            handle: t.handle(),
+            structure: t,
+        })
+    }
+
+    pub fn iter_by_type(&self, ty: u8) -> impl Iterator<Item = StructureEntry<'_>> {
+        self.entries().filter(move |e| e.ty == ty)
+    }
+
+    // Sort by type and then handle, with the End-of-Table structure always last
+    pub fn reorder_tables(&mut self) {
+        self.tables.sort_by_key(|t| {
+            (
+                t.structure_type() == EndOfTable::TYPE_NUMBER,
+                t.structure_type(),
+                t.handle(),
+            )
+        });
+    }
+
+    pub fn has_type(&self, ty: u8) -> bool {
+        self.iter_by_type(ty).next().is_some()
+    }
+
+    pub fn count_of_type(&self, ty: u8) -> usize {
+        self.iter_by_type(ty).count()
+    }
+
+    pub fn handles_of_type(&self, ty: u8) -> Vec<u16> {
+        self.iter_by_type(ty).map(|e| e.handle).collect()
+    }
+
+    pub fn processor_count(&self) -> usize {
+        self.count_of_type(ProcessorInformation::TYPE_NUMBER)
+    }
+
+    pub fn memory_device_count(&self) -> usize {
+        self.count_of_type(MemoryDevice::TYPE_NUMBER)
+    }
+
+    pub fn memory_array_count(&self) -> usize {
+        self.count_of_type(PhysicalMemoryArray::TYPE_NUMBER)
+    }
+
+    // Returns the required types which are missing from the set
+    pub fn has_required_types(&self) -> Vec<u8> {
+        REQUIRED_TYPES
+            .iter()
+            .copied()
+            .filter(|ty| !self.has_type(*ty))
+            .collect()
+    }
+
+    pub fn has_required_smbios_types(&self) -> bool {
+        self.has_required_types().is_empty()
+    }
+
+    // Serializes each structure on its own, and checks that its header and string section are
+    // consistent with what it reports about itself
+    pub fn verify_structure_layout(&self) -> Result<(), Vec<LayoutError>> {
+        let mut errors = Vec::new();
+        for table in self.iter() {
+            let mut error = |problem| {
+                errors.push(LayoutError {
+                    handle: table.handle(),
+                    problem,
+                })
+            };
+
+            let mut output = Vec::new();
+            table.serialize(&mut output);
+            if output.len() != table.byte_length() {
+                error(LayoutProblem::ByteLengthMismatch {
+                    byte_length: table.byte_length(),
+                    serialized: output.len(),
+                });
+            }
+            // The End-of-Table structure is always 6 bytes, followed by any padding
+            let structure = match table.structure_type() {
+                EndOfTable::TYPE_NUMBER => &output[..output.len().min(6)],
+                _ => &output[..],
+            };
+            if structure.len() < 4 {
+                error(LayoutProblem::MissingTerminator);
+                continue;
+            }
+            if structure[0] != table.structure_type() {
+                error(LayoutProblem::TypeMismatch {
+                    structure_type: table.structure_type(),
+                    type_byte: structure[0],
+                });
+            }
+            if !structure.ends_with(&[0, 0]) {
+                error(LayoutProblem::MissingTerminator);
+            }
+            match SimpleStructureIterator::new(structure).next() {
+                Some(Ok(header)) if header.raw().len() == structure.len() => (),
+                _ => error(LayoutProblem::LengthMismatch {
+                    length_byte: structure[1],
+                }),
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // Any End-of-Table padding is only counted in the total
+    pub fn statistics(&self) -> SmbiosStatistics {
+        let mut stats = SmbiosStatistics {
+            total_serialized_bytes: self.serialized_size(),
+            structure_count: self.len(),
+            max_structure_bytes: self.serialized_size(),
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        self.serialize(&mut output);
+        for header in SimpleStructureIterator::new(&output).flatten() {
+            *stats.type_counts.entry(header.ty).or_default() += 1;
+            stats.fixed_section_bytes += usize::from(header.length);
+            stats.string_bytes += header.raw().len() - usize::from(header.length);
+        }
+        stats
+    }
+
+    // The oldest spec version which supports every structure in the set
+    pub fn required_spec_version(&self) -> (u8, u8) {
+        self.iter()
+            .map(|t| t.minimum_spec_version())
+            .max()
+            .unwrap_or((2, 0))
+    }
+
+    // The version for the entry point: the crate's version, unless the set needs a newer one
+    pub fn entry_point_version(&self) -> (u8, u8) {
+        self.required_spec_version().max(SMBIOS_VERSION)
+    }
+
+    pub fn spec_warnings(&self) -> Vec<SpecWarning> {
+        self.iter()
+            .filter(|t| t.minimum_spec_version() > SMBIOS_VERSION)
+            .map(|t| SpecWarning {
+                handle: t.handle(),
+                structure_type: t.structure_type(),
+                required: t.minimum_spec_version(),
+            })
+            .collect()
+    }
+
+    // A one-line summary of each structure, for firmware logs. Processor, memory array, memory
+    // device and memory mapping structures are summarized from their fields; for the rest, and for
+    // parsed structures, only the strings are read back from the serialized form.
+    pub fn dump_ascii(&self) -> String {
+        let mut output = Vec::new();
+        self.serialize(&mut output);
+
+        let mut dump = String::new();
+        let headers = SimpleStructureIterator::new(&output).flatten();
+        for (table, header) in self.iter().zip(headers) {
+            let raw = header.raw();
+            let length = usize::from(header.length);
+            let byte = |i: usize| if i < length { raw[i] } else { 0 };
+            let string = |i: usize| header.get_string(byte(i)).unwrap_or("");
+            let any = table.as_any();
+
+            write!(
+                dump,
+                "Type {} ({}) h={:#06x}:",
+                header.ty,
+                structure_name(header.ty),
+                header.handle
+            )
+            .unwrap();
+            if let Some(p) = any.downcast_ref::<ProcessorInformation>() {
+                write!(
+                    dump,
+                    " socket=\"{}\" manufacturer=\"{}\" ver=\"{}\" max_speed={}MHz",
+                    p.get_socket_designation(),
+                    p.get_processor_manufacturer(),
+                    p.get_processor_version(),
+                    p.get_max_speed_mhz()
+                )
+            } else if let Some(a) = any.downcast_ref::<PhysicalMemoryArray>() {
+                write!(dump, " devices={}", a.get_number_of_memory_devices())
+            } else if let Some(m) = any.downcast_ref::<MemoryDevice>() {
+                write!(
+                    dump,
+                    " locator=\"{}\" bank=\"{}\" ",
+                    m.get_device_locator(),
+                    m.get_bank_locator()
+                )
+                .and_then(|_| match m.get_memory_size() {
+                    Some(size) => write!(dump, "size={}MB", size >> 20),
+                    None => write!(dump, "size=unknown"),
+                })
+                .and_then(|_| write!(dump, " speed={}MT/s", m.get_speed_mts()))
+            } else if let Some(a) = any.downcast_ref::<MemoryArrayMappedAddress>() {
+                let (starting, ending) = a.get_address_range();
+                write!(dump, " range={starting:#x}-{ending:#x}")
+            } else if let Some(d) = any.downcast_ref::<MemoryDeviceMappedAddress>() {
+                let (starting, ending) = d.get_address_range();
+                write!(dump, " range={starting:#x}-{ending:#x}")
+            } else {
+                match header.ty {
+                    0 => write!(
+                        dump,
+                        " vendor=\"{}\" ver=\"{}\" date=\"{}\"",
+                        string(0x4),
+                        string(0x5),
+                        string(0x8)
+                    ),
+                    1 => write!(
+                        dump,
+                        " manufacturer=\"{}\" product=\"{}\" ver=\"{}\" serial=\"{}\"",
+                        string(0x4),
+                        string(0x5),
+                        string(0x6),
+                        string(0x7)
+                    ),
+                    2 | 3 => write!(dump, " manufacturer=\"{}\"", string(0x4)),
+                    7..=9 => write!(dump, " designation=\"{}\"", string(0x4)),
+                    11 => write!(dump, " strings={}", byte(0x4)),
+                    _ => write!(dump, " length={:#x}", header.length),
+                }
+            }
+            .unwrap();
+            dump.push('\n');
+        }
+        dump
+    }
+
+    pub fn serialized_size(&self) -> usize {
+        self.byte_length()
+    }
+
+    // Returns the number of bytes written
+    pub fn write_to_slice(&self, buf: &mut [u8]) -> Result<usize, SmbiosError> {
+        check_buffer(self.serialized_size(), buf)?;
+        let mut sink = SliceSink::new(buf);
+        self.serialize(&mut sink);
+        Ok(sink.bytes_written())
+    }
+
+    // Writes the entry point for this set, when the table is at `table_address`
+    pub fn write_entry_point_to_slice(
+        &self,
+        table_address: u64,
+        buf: &mut [u8],
+    ) -> Result<(), SmbiosError> {
+        let entry_point = EntryPoint::with_version(
+            self.serialized_size().try_into().unwrap(),
+            table_address,
+            self.entry_point_version(),
+        );
+        check_buffer(entry_point.byte_length(), buf)?;
+        entry_point.serialize(&mut SliceSink::new(buf));
+        Ok(())
+    }
+
+    // Sizes of the Type 17 devices. Parsed structures aren't `MemoryDevice`s, so they're skipped.
+    fn memory_device_sizes(&self) -> impl Iterator<Item = Option<u64>> + '_ {
+        self.iter()
+            .filter_map(|t| t.as_any().downcast_ref::<MemoryDevice>())
+            .map(|device| device.get_memory_size())
+    }
+
+    // The handle of the first Type 19 structure whose range contains the device's range
+    pub fn find_memory_array_mapped_address(
+        &self,
+        device_start: u64,
+        device_end: u64,
+    ) -> Option<u16> {
+        self.iter()
+            .filter_map(|t| t.as_any().downcast_ref::<MemoryArrayMappedAddress>())
+            .find(|array| {
+                let (starting, ending) = array.get_address_range();
+                starting <= device_start && device_end <= ending
+            })
+            .map(|array| array.get_handle())
+    }
+
+    // The handle of the Type 16 structure that a Type 17 structure refers to, if both are in
+    // the set
+    pub fn find_physical_memory_array_for_device(&self, device_handle: u16) -> Option<u16> {
+        let array_handle = self
+            .iter()
+            .filter_map(|t| t.as_any().downcast_ref::<MemoryDevice>())
+            .find(|device| device.get_handle() == device_handle)?
+            .get_physical_memory_array_handle();
+        self.iter_by_type(PhysicalMemoryArray::TYPE_NUMBER)
+            .any(|e| e.handle == array_handle)
+            .then_some(array_handle)
+    }
+
+    // Makes the ECC widths of the array's memory devices match its error correction type, so the
+    // two can't disagree. Devices whose data width is unknown are skipped. Returns the number of
+    // devices updated, which is 0 if the array isn't in the set as a `PhysicalMemoryArray`.
+    pub fn propagate_ecc_to_memory_devices(&mut self, array_handle: MemoryArrayHandle) -> usize {
+        let Some(ecc) = self.tables.iter_mut().find_map(|t| {
+            let array = t.as_any_mut().downcast_mut::<PhysicalMemoryArray>()?;
+            (array.get_handle() == array_handle).then(|| array.get_memory_error_correction())
+        }) else {
+            return 0;
+        };
+
+        let mut updated = 0;
+        for t in &mut self.tables {
+            if let Some(device) = t.as_any_mut().downcast_mut::<MemoryDevice>() {
+                if device.get_physical_memory_array_handle() == array_handle
+                    && device.set_ecc_width_for(ecc)
+                {
+                    updated += 1;
+                }
+            }
+        }
+        updated
+    }
+
+    // Devices of unknown size don't contribute to the total
+    pub fn total_memory_bytes(&self) -> u64 {
+        self.memory_device_sizes().flatten().sum()
+    }
+
+    // Devices of unknown size are installed, but empty sockets (size 0) are not
+    pub fn present_memory_device_count(&self) -> usize {
+        self.memory_device_sizes()
+            .filter(|size| *size != Some(0))
+            .count()
+    }
+}
+
+fn check_buffer(needed: usize, buf: &[u8]) -> Result<(), SmbiosError> {
+    if buf.len() < needed {
+        return Err(SmbiosError::BufferTooSmall {
+            needed,
+            available: buf.len(),
+        });
+    }
+    Ok(())
+}
+
+fn structure_name(ty: u8) -> &'static str {
+    match ty {
+        0 => "BIOS",
+        1 => "System",
+        2 => "Baseboard",
+        3 => "Chassis",
+        4 => "Processor",
+        5 => "Memory Controller",
+        7 => "Cache",
+        8 => "Port Connector",
+        9 => "System Slot",
+        10 => "Onboard Devices",
+        11 => "OEM Strings",
+        14 => "Group Associations",
+        15 => "System Event Log",
+        16 => "Physical Memory Array",
+        17 => "Memory Device",
+        19 => "Memory Array Mapped Address",
+        20 => "Memory Device Mapped Address",
+        22 => "Portable Battery",
+        26 => "Voltage Probe",
+        28 => "Temperature Probe",
+        29 => "Current Probe",
+        32 => "System Boot",
+        37 => "Memory Channel",
+        40 => "Additional Information",
+        44 => "Processor Additional Information",
+        45 => "Firmware Inventory",
+        127 => "End-of-Table",
+        128..=255 => "OEM",
+        _ => "Other",
+    }
+}
+
+// Rewrites the structure's own handle and its references to other structures using `remap`.
+// Handles which aren't in `remap` are left as they are.
+fn relocate_handles(
+    mut table: Box<dyn SmbiosTable>,
+    remap: &BTreeMap<u16, u16>,
+) -> Box<dyn SmbiosTable> {
+    table.remap_handles(&|handle| remap.get(&handle).copied().unwrap_or(handle));
+    table
+}
+
+// Builds a set of tables which always ends with an End-of-Table structure
+#[derive(Default)]
+pub struct SmbiosTableSetBuilder {
+    set: SmbiosTableSet,
+}
+
+impl SmbiosTableSetBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, table: impl SmbiosTable + 'static) -> &mut Self {
+        self.set.add(table);
+        self
+    }
+
+    // The End-of-Table structure gets the next free handle. If there isn't one, the structures
+    // are left in the builder.
+    pub fn build(&mut self) -> Result<SmbiosTableSet, SmbiosError> {
+        let handle = self.set.next_free_handle()?;
+        let mut set = mem::take(&mut self.set);
+        set.add(EndOfTable::new(handle));
+        Ok(set)
+    }
+
+    // The entry point's structure table maximum size is the size of the whole table
+    pub fn build_and_finalize(
+        &mut self,
+        table_address: u64,
+    ) -> Result<(SmbiosTableSet, EntryPoint), SmbiosError> {
+        let set = self.build()?;
+        let entry_point = EntryPoint::with_version(
+            set.byte_length().try_into().unwrap(),
+            table_address,
+            set.entry_point_version(),
+        );
+        Ok((set, entry_point))
+    }
+}
+
+// A memory device at a known physical address, which is added to a set along with the Type 20
+// structure that maps it
+pub struct MemoryDeviceWithAddress {
+    device: MemoryDevice,
+    base: u64,
+    size: u64,
+}
+
+impl MemoryDeviceWithAddress {
+    pub fn new(device: MemoryDevice, base: u64, size: u64) -> Self {
+        Self { device, base, size }
+    }
+
+    // The device's size is set from the mapping, and the Type 20 structure gets the next free
+    // handle. Both go before any End-of-Table structure. Returns the handle of the Type 20
+    // structure. A size of 0, or a range which runs past the end of the address space, is an
+    // `InvalidAddressRange` whose ending has wrapped around.
+    pub fn finalize(
+        mut self,
+        set: &mut SmbiosTableSet,
+        array_mapped_handle: u16,
+    ) -> Result<u16, SmbiosError> {
+        let ending = self
+            .size
+            .checked_sub(1)
+            .and_then(|last| self.base.checked_add(last))
+            .ok_or(SmbiosError::InvalidAddressRange {
+                starting: self.base,
+                ending: self.base.wrapping_add(self.size).wrapping_sub(1),
+            })?;
+
+        let device_handle = self.device.get_handle();
+        self.device.set_memory_size(Some(self.size));
+        let device_index = set.insert_before_end_of_table(Box::new(self.device));
+        let handle = match set.next_free_handle() {
+            Ok(handle) => handle,
+            Err(e) => {
+                set.tables.remove(device_index);
+                return Err(e);
+            }
+        };
+
+        let mut mapping = MemoryDeviceMappedAddress::new(handle);
+        mapping.set_address_range(self.base, ending);
+        mapping.set_memory_device_handle(device_handle.into());
+        mapping.set_memory_array_mapped_address_handle(array_mapped_handle.into());
+        set.insert_before_end_of_table(Box::new(mapping));
+        Ok(handle)
+    }
+}
+
+impl Clone for SmbiosTableSet {
+    fn clone(&self) -> Self {
+        Self {
+            tables: self.tables.iter().map(|t| t.clone_box()).collect(),
+        }
+    }
+}
+
+impl SmbiosStructure for SmbiosTableSet {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        let mut offset = 0;
+        for table in &self.tables {
+            table.serialize_at(sink, offset);
+            offset += table.byte_length_at(offset);
+        }
+    }
+
+    // Structures may pad themselves, depending on where they fall in the table
+    fn byte_length(&self) -> usize {
+        self.tables
+            .iter()
+            .fold(0, |offset, t| offset + t.byte_length_at(offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::*;
+    use crate::validation::validate_address_ranges;
+    use alloc::vec;
+
+    #[test]
+    fn test_reorder_tables() {
+        let mut set = SmbiosTableSet::new();
+        set.add(EndOfTable::new(6));
+        set.add(OemStrings::new(5));
+        set.add(MemoryDevice::new(4));
+        set.add(MemoryDevice::new(3));
+        set.add(ProcessorInformation::new(2));
+        set.add(SystemInformation::new(1));
+        set.add(BiosInformation::new(0));
+        set.reorder_tables();
+
+        let order: Vec<(u8, u16)> = set
+            .iter()
+            .map(|t| (t.structure_type(), t.handle()))
+            .collect();
+        assert_eq!(
+            order,
+            [(0, 0), (1, 1), (4, 2), (11, 5), (17, 3), (17, 4), (127, 6)]
+        );
+
+        let mut output = vec![];
+        set.serialize(&mut output);
+        assert_eq!(output.len(), set.byte_length());
+        assert_eq!(&output[0..4], &[0, 0x14, 0, 0]);
+        assert_eq!(&output[output.len() - 6..], &[127, 4, 6, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_total_memory() {
+        let mut set = SmbiosTableSet::new();
+        for (handle, size) in [
+            (1, Some(32 << 30)),
+            (2, Some(16 << 30)),
+            (3, Some(0)),
+            (4, None),
+        ] {
+            let mut m = MemoryDevice::new(handle);
+            m.set_memory_size(size);
+            set.add(m);
+        }
+        set.add(PhysicalMemoryArray::new(5));
+
+        assert_eq!(set.total_memory_bytes(), 48 << 30);
+        assert_eq!(set.present_memory_device_count(), 3);
+    }
+
+    #[test]
+    fn test_find_memory_arrays() {
+        // Two arrays of two 16 GiB DIMMs, with each array mapped contiguously
+        let mut set = SmbiosTableSet::new();
+        for (array, base) in [(0x1000, 0x8000_0000u64), (0x1001, 0x10_8000_0000)] {
+            set.add(PhysicalMemoryArray::new(array));
+            for dimm in 0..2 {
+                let handle = array + 0x10 * (dimm + 1);
+                let m = MemoryDevice::new_present(
+                    handle,
+                    array,
+                    16 << 30,
+                    MemoryType::Ddr5,
+                    FormFactor::Dimm,
+                    "DIMM",
+                    "BANK",
+                    4800,
+                );
+                set.add(m);
+            }
+            let mut a = MemoryArrayMappedAddress::new(array + 0x100);
+            a.set_address_range(base, base + (32 << 30) - 1);
+            set.add(a);
+        }
+        let mut orphan = MemoryDevice::new(0x2000);
+        orphan.set_physical_memory_array_handle(0x3000.into());
+        set.add(orphan);
+
+        assert_eq!(
+            set.find_memory_array_mapped_address(0x8000_0000, 0x4_7fff_ffff),
+            Some(0x1100)
+        );
+        assert_eq!(
+            set.find_memory_array_mapped_address(0x14_8000_0000, 0x18_7fff_ffff),
+            Some(0x1101)
+        );
+        // Straddles both arrays
+        assert_eq!(
+            set.find_memory_array_mapped_address(0x4_8000_0000, 0x10_8fff_ffff),
+            None
+        );
+
+        assert_eq!(
+            set.find_physical_memory_array_for_device(0x1010),
+            Some(0x1000)
+        );
+        assert_eq!(
+            set.find_physical_memory_array_for_device(0x1021),
+            Some(0x1001)
+        );
+        assert_eq!(set.find_physical_memory_array_for_device(0x2000), None);
+        assert_eq!(set.find_physical_memory_array_for_device(0x1000), None);
+    }
+
+    #[test]
+    fn test_propagate_ecc_to_memory_devices() {
+        let mut set = SmbiosTableSet::new();
+        let mut array = PhysicalMemoryArray::new(0x10);
+        array.set_memory_error_correction(ErrorCorrectionType::SingleBitEcc);
+        set.add(array);
+
+        // Two DDR5 RDIMMs on the array, with the ECC bits left out of their total width
+        for handle in [0x20, 0x21] {
+            let mut m = MemoryDevice::new(handle);
+            m.set_physical_memory_array_handle(0x10.into());
+            m.set_memory_type(MemoryType::Ddr5);
+            m.set_type_detail_flags(TypeDetail::DDR5_RDIMM);
+            m.set_module_dimensions(64, 0).unwrap();
+            set.add(m);
+        }
+        // An empty slot's widths are unknown, so there's nothing to widen
+        set.add(MemoryDevice::new_empty_slot(0x23, 0x10, "DIMM 3"));
+        // A device on another array is left alone
+        let mut other = MemoryDevice::new(0x22);
+        other.set_physical_memory_array_handle(0x11.into());
+        other.set_module_dimensions(64, 0).unwrap();
+        set.add(other);
+
+        assert_eq!(set.propagate_ecc_to_memory_devices(0x10), 2);
+        let mut output = vec![];
+        set.serialize(&mut output);
+        let widths: Vec<(u16, u16)> = SimpleStructureIterator::new(&output)
+            .flatten()
+            .filter(|h| h.ty == MemoryDevice::TYPE_NUMBER)
+            .map(|h| {
+                let raw = h.raw();
+                (
+                    u16::from_le_bytes([raw[8], raw[9]]),
+                    u16::from_le_bytes([raw[0xa], raw[0xb]]),
+                )
+            })
+            .collect();
+        assert_eq!(widths, [(72, 64), (72, 64), (0xffff, 0xffff), (64, 64)]);
+
+        assert_eq!(set.propagate_ecc_to_memory_devices(0x11), 0);
+    }
+
+    #[test]
+    fn test_memory_device_with_address() {
+        let mut set = SmbiosTableSet::new();
+        set.add(PhysicalMemoryArray::new(0x10));
+        let mut array = MemoryArrayMappedAddress::new(0x13);
+        array.set_address_range(0x10_0000_0000, 0x13_ffff_ffff);
+        set.add(array);
+
+        let device = MemoryDevice::new_with_array(0x11, 0x10, "DIMM0", "BANK 0");
+        let handle = MemoryDeviceWithAddress::new(device, 0x10_0000_0000, 16 << 30)
+            .finalize(&mut set, 0x13)
+            .unwrap();
+        assert_eq!(handle, 0x14);
+        assert_eq!(set.total_memory_bytes(), 16 << 30);
+        assert!(validate_address_ranges(&set).is_empty());
+
+        let mapping = set.iter().last().unwrap();
+        let mut output = vec![];
+        mapping.serialize(&mut output);
+        assert_eq!((output[0], mapping.handle()), (20, 0x14));
+        // Device handle, array mapped address handle and the extended range
+        assert_eq!(&output[0xc..0x10], &[0x11, 0, 0x13, 0]);
+        assert_eq!(&output[0x13..0x1b], &0x10_0000_0000u64.to_le_bytes());
+        assert_eq!(&output[0x1b..0x23], &0x13_ffff_ffffu64.to_le_bytes());
+
+        for (base, size) in [(0x14_0000_0000, 0), (u64::MAX - 0xfff, 0x2000)] {
+            let device = MemoryDevice::new_with_array(0x12, 0x10, "DIMM1", "BANK 1");
+            let result = MemoryDeviceWithAddress::new(device, base, size).finalize(&mut set, 0x13);
+            assert!(matches!(
+                result,
+                Err(SmbiosError::InvalidAddressRange { starting, ending }) if ending < starting
+            ));
+        }
+        assert_eq!(set.len(), 4);
+    }
+
+    #[test]
+    fn test_memory_device_with_address_before_end_of_table() {
+        let mut builder = SmbiosTableSetBuilder::new();
+        builder.add(PhysicalMemoryArray::new(0x10));
+        let mut set = builder.build().unwrap();
+
+        let device = MemoryDevice::new_with_array(0x20, 0x10, "DIMM0", "BANK 0");
+        let handle = MemoryDeviceWithAddress::new(device, 0, 16 << 30)
+            .finalize(&mut set, 0x13)
+            .unwrap();
+        assert_eq!(handle, 0x21);
+        let order: Vec<(u8, u16)> = set.entries().map(|e| (e.ty, e.handle)).collect();
+        assert_eq!(order, [(16, 0x10), (17, 0x20), (20, 0x21), (127, 0x11)]);
+    }
+
+    #[test]
+    fn test_builder() {
+        let (set, entry_point) = SmbiosTableSetBuilder::new()
+            .add(BiosInformation::new(0))
+            .add(SystemInformation::new(1))
+            .add(ProcessorInformation::new(4))
+            .build_and_finalize(0x8000_0000)
+            .unwrap();
+
+        let last = set.iter().last().unwrap();
+        assert_eq!((last.structure_type(), last.handle()), (127, 5));
+
+        let mut output = vec![];
+        entry_point.serialize(&mut output);
+        assert_eq!(output.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)), 0);
+        assert_eq!(
+            u32::from_le_bytes(output[0xc..0x10].try_into().unwrap()) as usize,
+            set.byte_length()
+        );
+        assert_eq!(&output[0x10..0x18], &0x8000_0000u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_builder_handles() {
+        // The handle after the highest one would be reserved, so the lowest unused one is used
+        let set = SmbiosTableSetBuilder::new()
+            .add(BiosInformation::new(0))
+            .add(SystemInformation::new(MAX_HANDLE))
+            .build()
+            .unwrap();
+        assert_eq!(set.iter().last().unwrap().handle(), 1);
+
+        let mut builder = SmbiosTableSetBuilder::new();
+        for handle in 0..=MAX_HANDLE {
+            builder.add(EndOfTable::new(handle));
+        }
+        assert_eq!(
+            builder.build().err(),
+            Some(SmbiosError::HandleSpaceExhausted)
+        );
+        // The structures are still in the builder
+        builder.add(SystemInformation::new(0xff00));
+        assert_eq!(
+            builder.build().err(),
+            Some(SmbiosError::HandleSpaceExhausted)
+        );
+    }
+
+    #[test]
+    fn test_write_to_slice() {
+        let mut b = BiosInformation::new(0);
+        b.set_vendor("Vendor");
+        let mut m = MemoryDevice::new(2);
+        m.set_memory_size(Some(16 << 30));
+        let set = SmbiosTableSetBuilder::new()
+            .add(b)
+            .add(SystemInformation::new(1))
+            .add(m)
+            .build()
+            .unwrap();
+
+        let mut buf = [0xaa; 4096];
+        let len = set.write_to_slice(&mut buf).unwrap();
+        let mut output = vec![];
+        set.serialize(&mut output);
+        assert_eq!(len, set.serialized_size());
+        assert_eq!(&buf[..len], output.as_slice());
+        assert_eq!(buf[len], 0xaa);
+
+        let mut entry_point = [0; 0x18];
+        set.write_entry_point_to_slice(0x1000, &mut entry_point)
+            .unwrap();
+        assert_eq!(&entry_point[0..5], b"_SM3_");
+
+        let mut small = [0; 16];
+        assert_eq!(
+            set.write_to_slice(&mut small),
+            Err(SmbiosError::BufferTooSmall {
+                needed: len,
+                available: 16
+            })
+        );
+        assert!(set.write_entry_point_to_slice(0x1000, &mut small).is_err());
+    }
+
+    #[test]
+    fn test_end_of_table_padding() {
+        let mut b = BiosInformation::new(0);
+        b.set_vendor("Vendo");
+        let mut set = SmbiosTableSet::new();
+        set.add(b);
+        set.add(EndOfTable::with_padding(1));
+
+        // 0x14 + 7 bytes of BIOS information and 6 bytes of End-of-Table is 33 bytes
+        let mut output = vec![];
+        set.serialize(&mut output);
+        assert_eq!(output.len(), 36);
+        assert_eq!(set.serialized_size(), 36);
+        assert_eq!(&output[27..], &[127, 4, 1, 0, 0, 0, 0, 0, 0]);
+
+        let mut buf = [0xaa; 64];
+        assert_eq!(set.write_to_slice(&mut buf), Ok(36));
+        assert_eq!(&buf[33..37], &[0, 0, 0, 0xaa]);
+
+        // Padding is relative to the start of the table, not the start of the sink
+        let mut prefixed = vec![0xaa, 0xbb];
+        set.serialize(&mut prefixed);
+        assert_eq!(prefixed.len(), 2 + 36);
+        assert_eq!(&prefixed[2..], output.as_slice());
+
+        // On its own, the structure is padded as if it started the table
+        let e = EndOfTable::with_padding(1);
+        let mut prefixed = vec![0xaa, 0xbb];
+        e.serialize(&mut prefixed);
+        assert_eq!(e.byte_length(), 8);
+        assert_eq!(&prefixed[2..], &[127, 4, 1, 0, 0, 0, 0, 0]);
+
+        let mut e = EndOfTable::new(1);
+        e.set_alignment_bytes(16);
+        let mut set = SmbiosTableSet::new();
+        set.add(e);
+        assert_eq!(set.serialized_size(), 16);
+    }
+
+    // A structure with a bad length byte, which puts part of its fixed section in the string
+    // section
+    struct BadLength;
+
+    impl SmbiosStructure for BadLength {
+        fn serialize(&self, sink: &mut dyn Sink) {
+            sink.vec(&[0x80, 4, 9, 0, 1, 0, 0, 0, 0]);
+        }
+
+        fn byte_length(&self) -> usize {
+            9
+        }
+    }
+
+    impl SmbiosTable for BadLength {
+        fn structure_type(&self) -> u8 {
+            0x81
+        }
+
+        fn handle(&self) -> u16 {
+            9
+        }
+
+        fn clone_box(&self) -> Box<dyn SmbiosTable> {
+            Box::new(BadLength)
+        }
+
+        fn remap_handles(&mut self, _remap: &dyn Fn(u16) -> u16) {}
+    }
+
+    // A structure from a spec version newer than the crate's
+    #[derive(Clone)]
+    struct FutureStructure;
+
+    impl SmbiosStructure for FutureStructure {
+        fn serialize(&self, sink: &mut dyn Sink) {
+            sink.vec(&[0x30, 4, 7, 0, 0, 0]);
+        }
+
+        fn byte_length(&self) -> usize {
+            6
+        }
+    }
+
+    impl SmbiosTable for FutureStructure {
+        fn structure_type(&self) -> u8 {
+            0x30
+        }
+
+        fn handle(&self) -> u16 {
+            7
+        }
+
+        fn clone_box(&self) -> Box<dyn SmbiosTable> {
+            Box::new(self.clone())
+        }
+
+        fn remap_handles(&mut self, _remap: &dyn Fn(u16) -> u16) {}
+
+        fn minimum_spec_version(&self) -> (u8, u8) {
+            (SMBIOS_VERSION.0, SMBIOS_VERSION.1 + 1)
+        }
+    }
+
+    #[test]
+    fn test_required_spec_version() {
+        let mut builder = SmbiosTableSetBuilder::new();
+        builder
+            .add(BiosInformation::new(0))
+            .add(SystemInformation::new(1))
+            .add(MemoryDevice::new(2))
+            .add(SystemBootInformation::new(3, BootStatus::NoErrorsDetected).unwrap());
+        let set = builder.build().unwrap();
+        assert_eq!(set.required_spec_version(), (2, 3));
+        assert_eq!(set.entry_point_version(), SMBIOS_VERSION);
+        assert!(set.spec_warnings().is_empty());
+
+        let mut set = SmbiosTableSetBuilder::new()
+            .add(FirmwareInventoryInformation::new(0))
+            .build()
+            .unwrap();
+        assert_eq!(set.required_spec_version(), (3, 5));
+        assert!(set.spec_warnings().is_empty());
+
+        set.add(FutureStructure);
+        let newer = (SMBIOS_VERSION.0, SMBIOS_VERSION.1 + 1);
+        assert_eq!(set.required_spec_version(), newer);
+        assert_eq!(
+            set.spec_warnings(),
+            [SpecWarning {
+                handle: 7,
+                structure_type: 0x30,
+                required: newer
+            }]
+        );
+        let mut entry_point = [0; 0x18];
+        set.write_entry_point_to_slice(0x1000, &mut entry_point)
+            .unwrap();
+        let entry_point = EntryPoint::from_bytes(&entry_point).unwrap();
+        assert_eq!(entry_point.get_version(), newer);
+    }
+
+    #[test]
+    fn test_verify_structure_layout() {
+        let mut b = BiosInformation::new(0);
+        b.set_vendor("Vendor");
+        let mut o = OemStrings::new(1);
+        o.add_string("OEM");
+        let mut builder = SmbiosTableSetBuilder::new();
+        builder
+            .add(b)
+            .add(o)
+            .add(SystemBootInformation::new(2, BootStatus::NoErrorsDetected).unwrap())
+            .add(EndOfTable::with_padding(3));
+        let mut set = builder.build().unwrap();
+        assert_eq!(set.verify_structure_layout(), Ok(()));
+
+        set.add(BadLength);
+        assert_eq!(
+            set.verify_structure_layout(),
+            Err(vec![
+                LayoutError {
+                    handle: 9,
+                    problem: LayoutProblem::TypeMismatch {
+                        structure_type: 0x81,
+                        type_byte: 0x80
+                    }
+                },
+                LayoutError {
+                    handle: 9,
+                    problem: LayoutProblem::LengthMismatch { length_byte: 4 }
+                },
+            ])
+        );
+    }
+
+    // One socket's worth of structures: a processor with its caches, and a memory array with one
+    // device and its mappings
+    fn socket_set(base: u64) -> SmbiosTableSet {
+        let mut builder = SmbiosTableSetBuilder::new();
+        builder
+            .add(CacheInformation::new_l1_data(0x700))
+            .add(CacheInformation::new_l2_unified(0x701))
+            .add(CacheInformation::new_l3_unified(0x702));
+        let mut p = ProcessorInformation::new(0x400);
+        p.set_cache_handles(Some(0x700), Some(0x701), Some(0x702));
+        let mut array = PhysicalMemoryArray::new(0x1000);
+        array.set_memory_capacity(64 << 30);
+        let mut m = MemoryDevice::new(0x1100);
+        m.set_physical_memory_array_handle(0x1000.into());
+        m.set_memory_size(Some(32 << 30));
+        let mut array_mapped = MemoryArrayMappedAddress::new(0x1300);
+        array_mapped.set_address_range(base, base + (32 << 30) - 1);
+        array_mapped.set_memory_array_handle(0x1000.into());
+        let mut device_mapped = MemoryDeviceMappedAddress::new(0x1400);
+        device_mapped.set_address_range(base, base + (32 << 30) - 1);
+        device_mapped.set_memory_device_handle(0x1100.into());
+        device_mapped.set_memory_array_mapped_address_handle(0x1300.into());
+        builder
+            .add(p)
+            .add(array)
+            .add(m)
+            .add(array_mapped)
+            .add(device_mapped)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_merge() {
+        let first = socket_set(0);
+        let first_len = first.len();
+        let merged = first.merge(socket_set(32 << 30)).unwrap();
+
+        // Both sets have an End-of-Table structure, but only one is kept
+        assert_eq!(merged.len(), first_len * 2 - 1);
+        let handles: BTreeSet<u16> = merged.iter().map(|t| t.handle()).collect();
+        assert_eq!(handles.len(), merged.len());
+        assert_eq!(
+            merged.iter().last().unwrap().structure_type(),
+            EndOfTable::TYPE_NUMBER
+        );
+        assert_eq!(merged.verify_structure_layout(), Ok(()));
+        assert!(validate_address_ranges(&merged).is_empty());
+
+        // Relocated structures are still the types they were built as
+        let devices: Vec<&MemoryDevice> = merged
+            .iter()
+            .filter_map(|t| t.as_any().downcast_ref::<MemoryDevice>())
+            .collect();
+        assert_eq!(devices.len(), 2);
+        assert_ne!(devices[1].get_handle(), 0x1100);
+
+        let mut output = vec![];
+        merged.serialize(&mut output);
+        let structures: Vec<_> = SimpleStructureIterator::new(&output).flatten().collect();
+        let word = |raw: &[u8], i: usize| u16::from_le_bytes([raw[i], raw[i + 1]]);
+        let find = |ty: u8| {
+            structures
+                .iter()
+                .filter(move |h| h.ty == ty)
+                .map(|h| h.raw())
+                .collect::<Vec<_>>()
+        };
+
+        // The second processor refers to the second set of caches
+        let processors = find(4);
+        let caches: Vec<u16> = find(7).iter().map(|raw| word(raw, 2)).collect();
+        assert_eq!(caches.len(), 6);
+        assert_eq!(
+            [0x1a, 0x1c, 0x1e].map(|i| word(processors[0], i)),
+            [0x700, 0x701, 0x702]
+        );
+        assert_eq!(
+            [0x1a, 0x1c, 0x1e].map(|i| word(processors[1], i)),
+            [caches[3], caches[4], caches[5]]
+        );
+
+        // As does the second memory device and its mappings
+        let arrays: Vec<u16> = find(16).iter().map(|raw| word(raw, 2)).collect();
+        let devices = find(17);
+        assert_eq!(word(devices[1], 4), arrays[1]);
+        let device_handle = word(devices[1], 2);
+        assert_ne!(device_handle, 0x1100);
+        assert_eq!(
+            merged.find_physical_memory_array_for_device(device_handle),
+            Some(arrays[1])
+        );
+        let array_mapped = find(19);
+        assert_eq!(word(array_mapped[1], 0xc), arrays[1]);
+        let device_mapped = find(20);
+        assert_eq!(word(device_mapped[1], 0xc), device_handle);
+        assert_eq!(word(device_mapped[1], 0xe), word(array_mapped[1], 2));
+    }
+
+    #[test]
+    fn test_merge_handle_space_exhausted() {
+        let mut full = SmbiosTableSet::new();
+        for handle in 0..=MAX_HANDLE {
+            full.add(EndOfTable::new(handle));
+        }
+        let mut other = SmbiosTableSet::new();
+        other.add(SystemInformation::new(1));
+        assert_eq!(
+            full.merge(other).err(),
+            Some(MergeError::HandleSpaceExhausted)
+        );
+    }
+
+    #[test]
+    fn test_from_raw_table_area() {
+        let mut b = BiosInformation::new(0);
+        b.set_vendor("Vendor");
+        b.set_bios_version("1.0");
+        let mut o = OemStrings::new(1);
+        o.add_string("OEM");
+        let mut c = MemoryChannel::new(2);
+        c.add_device(1, 0x20).unwrap();
+        let mut builder = SmbiosTableSetBuilder::new();
+        builder
+            .add(b)
+            .add(o)
+            .add(c)
+            .add(SystemBootInformation::new(3, BootStatus::NoErrorsDetected).unwrap())
+            .add(EndOfTable::with_padding(4));
+        let set = builder.build().unwrap();
+        let mut output = vec![];
+        set.serialize(&mut output);
+
+        let mut parsed = SmbiosTableSet::from_raw_table_area(&output).unwrap();
+        let mut reserialized = vec![];
+        parsed.serialize(&mut reserialized);
+        // The End-of-Table padding isn't kept
+        assert_eq!(reserialized, output[..reserialized.len()]);
+        let types: Vec<u8> = parsed.iter().map(|t| t.structure_type()).collect();
+        assert_eq!(types, [0, 11, 37, 32, 127]);
+        assert_eq!(parsed.iter().nth(2).unwrap().handle(), 2);
+
+        // Modify the parsed table and write it out again
+        parsed.add(SystemInformation::new(5));
+        parsed.reorder_tables();
+        assert_eq!(parsed.has_required_types(), [3, 4, 7, 9, 17, 19]);
+
+        assert_eq!(
+            SmbiosTableSet::from_raw_table_area(&output[..10]).err(),
+            Some(ParseError::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_dump_ascii() {
+        let mut b = BiosInformation::new(1);
+        b.set_vendor("RISC-V Firmware");
+        b.set_bios_version("1.0");
+        b.set_release_date_from_parts(1, 1, 2024).unwrap();
+        let mut m = MemoryDevice::new(0x1100);
+        m.set_locators("DIMM 0", "BANK 0");
+        m.set_memory_size(Some(16 << 30));
+        m.set_speed_mts(4800);
+        let mut array_mapped = MemoryArrayMappedAddress::new(0x1300);
+        array_mapped.set_address_range(0x8000_0000, 0x4_7fff_ffff);
+        let mut builder = SmbiosTableSetBuilder::new();
+        builder
+            .add(b)
+            .add(MemoryDevice::new(0x1101))
+            .add(m)
+            .add(array_mapped)
+            .add(MemoryChannel::new(0x1500));
+        let dump = builder.build().unwrap().dump_ascii();
+
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[0],
+            "Type 0 (BIOS) h=0x0001: vendor=\"RISC-V Firmware\" ver=\"1.0\" date=\"01/01/2024\""
+        );
+        assert!(lines[1].contains("size=0MB"));
+        assert_eq!(
+            lines[2],
+            "Type 17 (Memory Device) h=0x1100: locator=\"DIMM 0\" bank=\"BANK 0\" \
+             size=16384MB speed=4800MT/s"
+        );
+        assert!(lines[3].ends_with("range=0x80000000-0x47fffffff"));
+        assert!(lines[4].starts_with("Type 37 (Memory Channel) h=0x1500: length="));
+        assert!(lines[5].starts_with("Type 127 (End-of-Table) h=0x1501"));
+
+        // Speeds too large for the word-sized field are read from the extended one
+        let mut m = MemoryDevice::new(0x1100);
+        m.set_speed_mts(70000);
+        let mut p = ProcessorInformation::new(0x400);
+        p.set_socket_designation("CPU0");
+        p.set_max_speed_mhz(3000).unwrap();
+        let mut builder = SmbiosTableSetBuilder::new();
+        builder.add(p).add(m);
+        let dump = builder.build().unwrap().dump_ascii();
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Type 4 (Processor) h=0x0400: socket=\"CPU0\" manufacturer=\"\" ver=\"\" \
+             max_speed=3000MHz"
+        );
+        assert!(lines[1].ends_with(" speed=70000MT/s"));
+    }
+
+    #[test]
+    fn test_entries() {
+        let mut set = SmbiosTableSet::new();
+        set.add(SystemInformation::new(1));
+        set.add(BiosInformation::new(0));
+        set.add(MemoryDevice::new(0x11));
+        set.add(PhysicalMemoryArray::new(0x10));
+        set.add(MemoryDevice::new(0x12));
+
+        let types: Vec<u8> = set.entries().map(|e| e.ty).collect();
+        assert_eq!(types, [1, 0, 17, 16, 17]);
+        let handles: Vec<u16> = set.entries().map(|e| e.handle).collect();
+        assert_eq!(handles, [1, 0, 0x11, 0x10, 0x12]);
+        for e in set.entries() {
+            assert_eq!(e.structure.structure_type(), e.ty);
+        }
+
+        let devices: Vec<u16> = set
+            .iter_by_type(MemoryDevice::TYPE_NUMBER)
+            .map(|e| e.handle)
+            .collect();
+        assert_eq!(devices, [0x11, 0x12]);
+        assert_eq!(set.iter_by_type(EndOfTable::TYPE_NUMBER).count(), 0);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let mut b = BiosInformation::new(0);
+        b.set_vendor("Vendor");
+        let mut c = MemoryChannel::new(3);
+        c.add_device(1, 1).unwrap();
+        let mut builder = SmbiosTableSetBuilder::new();
+        builder
+            .add(b)
+            .add(MemoryDevice::new(1))
+            .add(MemoryDevice::new(2))
+            .add(c);
+        let (set, entry_point) = builder.build_and_finalize(0x1000).unwrap();
+        let stats = set.statistics();
+
+        let mut output = vec![];
+        entry_point.serialize(&mut output);
+        let max_size = u32::from_le_bytes(output[0xc..0x10].try_into().unwrap()) as usize;
+        assert_eq!(
+            stats,
+            SmbiosStatistics {
+                total_serialized_bytes: 0x14 + 8 + 2 * (0x64 + 2) + 10 + 2 + 6,
+                structure_count: 5,
+                max_structure_bytes: max_size,
+                type_counts: BTreeMap::from([(0, 1), (17, 2), (37, 1), (127, 1)]),
+                string_bytes: 8 + 2 * 2 + 2 + 2,
+                fixed_section_bytes: 0x14 + 2 * 0x64 + 10 + 4,
+            }
+        );
+        assert_eq!(stats.total_serialized_bytes, max_size);
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut b = BiosInformation::new(0);
+        b.set_vendor("Vendor");
+        let mut o = OemStrings::new(1);
+        o.add_string("OEM");
+        let set = SmbiosTableSetBuilder::new()
+            .add(b)
+            .add(o)
+            .add(SystemBootInformation::new(2, BootStatus::NoErrorsDetected).unwrap())
+            .build()
+            .unwrap();
+        let mut copy = set.clone();
+
+        let mut output = vec![];
+        set.serialize(&mut output);
+        let mut copy_output = vec![];
+        copy.serialize(&mut copy_output);
+        assert_eq!(output, copy_output);
+
+        // The copy is independent of the original
+        copy.add(SystemInformation::new(3));
+        assert_eq!(set.len() + 1, copy.len());
+    }
+
+    #[test]
+    fn test_has_required_types() {
+        let mut set = SmbiosTableSet::new();
+        assert_eq!(set.has_required_types(), REQUIRED_TYPES);
+
+        set.add(BiosInformation::new(0));
+        set.add(SystemInformation::new(1));
+        set.add(EndOfTable::new(2));
+        assert_eq!(set.has_required_types(), [3, 4, 7, 9, 17, 19, 32]);
+    }
+
+    #[test]
+    fn test_type_queries() {
+        let mut set = SmbiosTableSet::new();
+        set.add(BiosInformation::new(0));
+        set.add(SystemInformation::new(1));
+        set.add(ProcessorInformation::new(0x400));
+        set.add(ProcessorInformation::new(0x401));
+        set.add(PhysicalMemoryArray::new(0x1000));
+        for handle in 0x1100..0x1104 {
+            set.add(MemoryDevice::new(handle));
+        }
+
+        assert!(set.has_type(4));
+        assert!(!set.has_type(EndOfTable::TYPE_NUMBER));
+        assert_eq!(set.count_of_type(17), 4);
+        assert_eq!(set.count_of_type(127), 0);
+        assert_eq!(set.handles_of_type(4), [0x400, 0x401]);
+        assert!(set.handles_of_type(19).is_empty());
+        assert_eq!(set.processor_count(), 2);
+        assert_eq!(set.memory_device_count(), 4);
+        assert_eq!(set.memory_array_count(), 1);
+        assert!(!set.has_required_smbios_types());
+
+        set.add(SystemEnclosure::new(3));
+        set.add(CacheInformation::new_l1_data(7));
+        set.add(SystemSlots::new(9));
+        set.add(MemoryArrayMappedAddress::new(0x1300));
+        set.add(SystemBootInformation::new(32, BootStatus::NoErrorsDetected).unwrap());
+        set.add(EndOfTable::new(127));
+        assert!(set.has_required_smbios_types());
+    }
+}
@@ -2,12 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub use crate::types::*;
-use crate::{simple_smbios_structure, Sink, SmbiosStructure, StringIndex};
+use crate::{
+    Sink, SmbiosError, SmbiosStructure, SmbiosTable, StringIndex, HANDLE_NOT_PROVIDED,
+    HANDLE_NOT_SUPPORTED, HANDLE_NO_CACHE,
+};
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::fmt;
 use core::mem::size_of;
 use paste::paste;
-use zerocopy::{byteorder, byteorder::LE, AsBytes};
+use zerocopy::{byteorder, byteorder::LE, AsBytes, FromBytes, FromZeroes};
+
+#[cfg(feature = "legacy_types")]
+mod legacy;
+#[cfg(feature = "legacy_types")]
+pub use legacy::*;
+
+#[cfg(test)]
+mod random_tests;
 
 // SMBIOS structures little-endian
 type U16 = byteorder::U16<LE>;
@@ -18,14 +30,40 @@ type U128 = byteorder::U128<LE>;
 // A handle to another structure is always 16 bits wide
 type StructureHandle = U16;
 
+// Handles to specific types of structure
+pub type MemoryArrayHandle = u16;
+pub type MemoryErrorHandle = u16;
+pub type MemoryDeviceHandle = u16;
+pub type CacheHandle = u16;
+
 // Current spec revision
 const SMBIOS_MAJOR: u8 = 3;
 const SMBIOS_MINOR: u8 = 7;
 const SMBIOS_DOCREV: u8 = 0;
 
+// The (major, minor) spec version the structures in this crate are laid out for
+pub const SMBIOS_VERSION: (u8, u8) = (SMBIOS_MAJOR, SMBIOS_MINOR);
+
 // For some reason this is used to indicate SMBIOS 3+ in the entry point structure
 const SMBIOS_REVISION: u8 = 1;
 
+// The spec version which introduced each structure type. Types this doesn't know about,
+// including OEM types, are assumed to be available from 2.0.
+pub fn minimum_spec_version(structure_type: u8) -> (u8, u8) {
+    match structure_type {
+        0..=15 | 127 => (2, 0),
+        16..=22 => (2, 1),
+        23..=30 => (2, 2),
+        31..=39 => (2, 3),
+        40 | 41 => (2, 6),
+        42 => (3, 0),
+        43 => (3, 1),
+        44 => (3, 3),
+        45 | 46 => (3, 5),
+        _ => (2, 0),
+    }
+}
+
 fn to_mb(n: u64) -> u64 {
     n >> 20
 }
@@ -42,9 +80,19 @@ fn tb(n: u64) -> u64 {
     n << 40
 }
 
+// Fixed-width fields (e.g. from SPD) are padded with spaces or nulls
+pub fn trim_smbios_string(s: &str) -> &str {
+    s.trim_end_matches([' ', '\0'])
+}
+
+// The value which makes the bytes sum to zero
+fn checksum(bytes: &[u8]) -> u8 {
+    0u8.wrapping_sub(bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)))
+}
+
 // SMBIOS 3.0 64-bit Entry Point structure
 #[repr(C, packed)]
-#[derive(Copy, Clone, Default, Debug, AsBytes)]
+#[derive(Copy, Clone, Default, Debug, AsBytes, FromZeroes, FromBytes)]
 pub struct EntryPoint {
     anchor: [u8; 5],
     checksum: u8,
@@ -61,33 +109,143 @@ static_assertions::const_assert!(size_of::<EntryPoint>() == 0x18);
 
 impl EntryPoint {
     pub fn new(structure_max_size: u32, table_address: u64) -> Self {
+        Self::with_version(structure_max_size, table_address, SMBIOS_VERSION)
+    }
+
+    // The docrev is only meaningful for the crate's own version, so it's 0 for any other
+    pub fn with_version(structure_max_size: u32, table_address: u64, version: (u8, u8)) -> Self {
         let mut s = Self {
             anchor: *b"_SM3_",
             checksum: 0,
             length: size_of::<Self>() as u8,
-            major_version: SMBIOS_MAJOR,
-            minor_version: SMBIOS_MINOR,
-            docrev: SMBIOS_DOCREV,
+            major_version: version.0,
+            minor_version: version.1,
+            docrev: if version == SMBIOS_VERSION {
+                SMBIOS_DOCREV
+            } else {
+                0
+            },
             revision: SMBIOS_REVISION,
             _reserved: 0,
             structure_max_size: structure_max_size.into(),
             table_address: table_address.into(),
         };
 
-        // Calculate checksum as the value that makes the sum of the structure zero.
-        let mut sum: u8 = 0;
-        for b in s.as_bytes() {
-            sum = sum.wrapping_add(*b);
-        }
-        s.checksum = 0u8.wrapping_sub(sum);
+        s.checksum = checksum(s.as_bytes());
         s
     }
+
+    // Parses an entry point from the start of `bytes`, checking its anchor, length and checksum
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let s = Self::read_from_prefix(bytes)?;
+        let valid = s.anchor == *b"_SM3_"
+            && usize::from(s.length) == size_of::<Self>()
+            && checksum(s.as_bytes()) == 0;
+        valid.then_some(s)
+    }
+
+    pub fn get_structure_max_size(&self) -> u32 {
+        self.structure_max_size.get()
+    }
+
+    pub fn get_version(&self) -> (u8, u8) {
+        (self.major_version, self.minor_version)
+    }
+
+    pub fn get_table_address(&self) -> u64 {
+        self.table_address.get()
+    }
 }
 
 impl SmbiosStructure for EntryPoint {
     fn serialize(&self, sink: &mut dyn Sink) {
         sink.vec(self.as_bytes());
     }
+
+    fn byte_length(&self) -> usize {
+        size_of::<Self>()
+    }
+}
+
+// SMBIOS 2.1 32-bit Entry Point structure, for tables below 4 GiB. The intermediate section,
+// starting with the `_DMI_` anchor, has its own checksum.
+#[repr(C, packed)]
+#[derive(Copy, Clone, Default, Debug, AsBytes, FromZeroes, FromBytes)]
+pub struct EntryPoint2 {
+    anchor: [u8; 4],
+    checksum: u8,
+    length: u8,
+    major_version: u8,
+    minor_version: u8,
+    structure_max_size: U16,
+    revision: u8,
+    formatted_area: [u8; 5],
+    intermediate_anchor: [u8; 5],
+    intermediate_checksum: u8,
+    table_length: U16,
+    table_address: U32,
+    number_of_structures: U16,
+    bcd_revision: u8,
+}
+static_assertions::const_assert!(size_of::<EntryPoint2>() == 0x1f);
+
+impl EntryPoint2 {
+    const INTERMEDIATE_OFFSET: usize = 0x10;
+
+    pub fn new(
+        structure_max_size: u16,
+        table_length: u16,
+        table_address: u32,
+        number_of_structures: u16,
+    ) -> Self {
+        let mut s = Self {
+            anchor: *b"_SM_",
+            length: size_of::<Self>() as u8,
+            major_version: SMBIOS_MAJOR,
+            minor_version: SMBIOS_MINOR,
+            structure_max_size: structure_max_size.into(),
+            intermediate_anchor: *b"_DMI_",
+            table_length: table_length.into(),
+            table_address: table_address.into(),
+            number_of_structures: number_of_structures.into(),
+            bcd_revision: (SMBIOS_MAJOR << 4) | SMBIOS_MINOR,
+            ..Default::default()
+        };
+        // The intermediate checksum is included in the overall checksum, so it comes first
+        s.intermediate_checksum = checksum(&s.as_bytes()[Self::INTERMEDIATE_OFFSET..]);
+        s.checksum = checksum(s.as_bytes());
+        s
+    }
+
+    // Parses an entry point from the start of `bytes`, checking its anchors, length and
+    // checksums
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let s = Self::read_from_prefix(bytes)?;
+        let valid = s.anchor == *b"_SM_"
+            && usize::from(s.length) == size_of::<Self>()
+            && checksum(s.as_bytes()) == 0
+            && s.intermediate_anchor == *b"_DMI_"
+            && checksum(&s.as_bytes()[Self::INTERMEDIATE_OFFSET..]) == 0;
+        valid.then_some(s)
+    }
+
+    pub fn get_table_length(&self) -> u16 {
+        self.table_length.get()
+    }
+
+    pub fn get_table_address(&self) -> u32 {
+        self.table_address.get()
+    }
+}
+
+impl SmbiosStructure for EntryPoint2 {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        sink.vec(self.as_bytes());
+    }
+
+    fn byte_length(&self) -> usize {
+        size_of::<Self>()
+    }
 }
 
 // Type 0 SMBIOS table (BIOS Information)
@@ -108,6 +266,130 @@ simple_smbios_structure! {
 }
 static_assertions::const_assert!(size_of::<Type0Data>() == 0x14);
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RomSizeError {
+    NotMultipleOf64Kib,
+    TooLargeForLegacyField,
+}
+
+// The Type 0 BIOS ROM size is encoded as n, where the size is 64 KiB * (n + 1). The value 0xff
+// indicates that the size is 16 MiB or larger, and is stored in the extended field instead.
+pub struct RomSizeEncoder;
+
+impl RomSizeEncoder {
+    pub fn from_kib(kib: u32) -> Result<u8, RomSizeError> {
+        if kib == 0 || !kib.is_multiple_of(64) {
+            return Err(RomSizeError::NotMultipleOf64Kib);
+        }
+        match kib / 64 - 1 {
+            n @ 0..=0xfe => Ok(n as u8),
+            _ => Err(RomSizeError::TooLargeForLegacyField),
+        }
+    }
+
+    pub fn to_kib(encoded: u8) -> u32 {
+        (u32::from(encoded) + 1) * 64
+    }
+}
+
+// The BIOS runtime image ends at 1 MiB, so its size is (0x10000 - segment) * 16 bytes, which is
+// a whole number of KiB for every segment. A segment of 0 would be a 1 MiB image; it's also used
+// when the runtime size isn't applicable, such as on UEFI systems.
+pub const BIOS_RUNTIME_SIZE_ABOVE_1MB_SENTINEL: u16 = 0x0000;
+
+impl BiosInformation {
+    // Sizes of 0 or 1 MiB and above use the sentinel segment
+    pub fn set_runtime_size_kb(&mut self, kb: u32) {
+        self.data.bios_starting_address_segment = match kb {
+            1..=1023 => ((0x400 - kb) * 64) as u16,
+            _ => BIOS_RUNTIME_SIZE_ABOVE_1MB_SENTINEL,
+        }
+        .into();
+    }
+
+    pub fn get_runtime_size_kb(&self) -> u32 {
+        (0x10000 - u32::from(self.data.bios_starting_address_segment.get())) / 64
+    }
+
+    // Note: ROM sizes of 16 MiB and above need the extended BIOS ROM size field, which isn't
+    // supported yet.
+    pub fn set_bios_rom_size_kib(&mut self, kib: u32) -> Result<(), RomSizeError> {
+        self.data.bios_rom_size = RomSizeEncoder::from_kib(kib)?;
+        Ok(())
+    }
+
+    pub fn set_bios_characteristics_raw(&mut self, v: U64) {
+        self.data.bios_characteristics = v;
+    }
+
+    pub fn set_characteristics_flags(&mut self, flags: BiosCharacteristics) {
+        self.data.bios_characteristics = flags.bits().into();
+    }
+
+    pub fn set_characteristics_ex1_flags(&mut self, flags: BiosCharacteristicsEx1) {
+        self.data.bios_characteristics_ex1 = flags.bits();
+    }
+
+    pub fn set_characteristics_ex2_flags(&mut self, flags: BiosCharacteristicsEx2) {
+        self.data.bios_characteristics_ex2 = flags.bits();
+    }
+
+    pub fn set_all_characteristics(
+        &mut self,
+        main: BiosCharacteristics,
+        ex1: BiosCharacteristicsEx1,
+        ex2: BiosCharacteristicsEx2,
+    ) {
+        self.set_characteristics_flags(main);
+        self.set_characteristics_ex1_flags(ex1);
+        self.set_characteristics_ex2_flags(ex2);
+    }
+
+    // The spec requires the release date to be "mm/dd/yyyy"
+    pub fn set_release_date_from_parts(
+        &mut self,
+        month: u8,
+        day: u8,
+        year: u16,
+    ) -> Result<(), SmbiosError> {
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) || !(1900..=2999).contains(&year)
+        {
+            return Err(SmbiosError::InvalidDate);
+        }
+        self.set_bios_release_date(&alloc::format!("{month:02}/{day:02}/{year}"));
+        Ok(())
+    }
+
+    // Converts a "yyyy-mm-dd" date, as used by build systems, to the spec's format
+    pub fn set_release_date_iso(&mut self, iso: &str) -> Result<(), SmbiosError> {
+        let field = |s: Option<&str>, len: usize| {
+            s.filter(|s| s.len() == len && s.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|s| s.parse::<u16>().ok())
+                .ok_or(SmbiosError::InvalidDate)
+        };
+        let mut parts = iso.split('-');
+        let year = field(parts.next(), 4)?;
+        let month = field(parts.next(), 2)?;
+        let day = field(parts.next(), 2)?;
+        if parts.next().is_some() {
+            return Err(SmbiosError::InvalidDate);
+        }
+        self.set_release_date_from_parts(month as u8, day as u8, year)
+    }
+}
+
+impl fmt::Display for BiosInformation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Type 0 (BIOS Information): vendor=\"{}\", version=\"{}\", date=\"{}\"",
+            self.get_string(self.data.vendor),
+            self.get_string(self.data.bios_version),
+            self.get_string(self.data.bios_release_date)
+        )
+    }
+}
+
 // Type 1 SMBIOS table (System Information)
 simple_smbios_structure! {
     1,
@@ -118,7 +400,7 @@ simple_smbios_structure! {
             version: StringIndex,
             serial_number: StringIndex,
             uuid: [u8; 16],
-            wakeup_type: WakeupType,
+            wakeup_type: WakeupTypeRaw,
             sku_number: StringIndex,
             family: StringIndex,
         }
@@ -126,6 +408,280 @@ simple_smbios_structure! {
 }
 static_assertions::const_assert!(size_of::<Type1Data>() == 0x1b);
 
+// Special UUID values: not present in the system, or present but not currently set
+pub const UUID_NOT_PRESENT: [u8; 16] = [0u8; 16];
+pub const UUID_NOT_SETTABLE: [u8; 16] = [0xff; 16];
+
+// SMBIOS stores the first three UUID fields little-endian, where RFC 4122 has them big-endian.
+// The swap is its own inverse.
+fn swap_uuid_fields(mut bytes: [u8; 16]) -> [u8; 16] {
+    bytes[0..4].reverse();
+    bytes[4..6].reverse();
+    bytes[6..8].reverse();
+    bytes
+}
+
+impl SystemInformation {
+    pub fn set_wakeup_type_from_u8(&mut self, v: u8) {
+        self.data.wakeup_type = WakeupType::from_u8(v).into();
+    }
+
+    pub fn set_uuid_rfc4122(&mut self, bytes: [u8; 16]) {
+        self.data.uuid = swap_uuid_fields(bytes);
+    }
+
+    pub fn get_uuid_rfc4122(&self) -> [u8; 16] {
+        swap_uuid_fields(self.data.uuid)
+    }
+}
+
+impl fmt::Display for SystemInformation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Type 1 (System Information): manufacturer=\"{}\", product=\"{}\", version=\"{}\", \
+             serial=\"{}\", wakeup={:?}",
+            self.get_string(self.data.manufacturer),
+            self.get_string(self.data.product_name),
+            self.get_string(self.data.version),
+            self.get_string(self.data.serial_number),
+            WakeupType::from(self.data.wakeup_type)
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ContainedElement {
+    element_type: ContainedElementType,
+    minimum: u8,
+    maximum: u8,
+}
+
+// The same string functions as `simple_smbios_structure` generates: setting a string replaces
+// any existing one, and clearing it removes the string and renumbers the others
+macro_rules! enclosure_strings {
+    ($($field:ident),*) => {
+        fn remove_string(&mut self, index: StringIndex) {
+            if index == 0 {
+                return;
+            }
+            self.strings.remove(usize::from(index) - 1);
+            $(
+                if self.$field > index {
+                    self.$field -= 1;
+                }
+            )*
+        }
+
+        paste! {
+            $(
+                pub fn [<set_ $field>](&mut self, s: &str) {
+                    match self.$field {
+                        0 => {
+                            self.strings.push(s.into());
+                            self.$field = self.strings.len().try_into().unwrap();
+                        }
+                        i => self.strings[usize::from(i) - 1] = s.into(),
+                    }
+                }
+
+                pub fn [<clear_ $field>](&mut self) {
+                    let index = self.$field;
+                    self.$field = 0;
+                    self.remove_string(index);
+                }
+
+                // An empty string is stored as no string (index 0), rather than as an empty
+                // string
+                pub fn [<set_ $field _or_empty>](&mut self, s: &str) {
+                    if s.is_empty() {
+                        self.[<clear_ $field>]();
+                    } else {
+                        self.[<set_ $field>](s);
+                    }
+                }
+            )*
+        }
+    };
+}
+
+// Type 3 SMBIOS table (System Enclosure or Chassis)
+// Each contained element is 3 bytes (type, minimum and maximum), and the SKU number string
+// follows them, so the structure length depends on the number of elements.
+#[derive(Clone, Debug)]
+pub struct SystemEnclosure {
+    handle: u16,
+    manufacturer: StringIndex,
+    chassis_type: ChassisType,
+    chassis_lock: bool,
+    version: StringIndex,
+    serial_number: StringIndex,
+    asset_tag: StringIndex,
+    boot_up_state: ChassisState,
+    power_supply_state: ChassisState,
+    thermal_state: ChassisState,
+    security_status: ChassisSecurityStatus,
+    oem_defined: u32,
+    height: u8,
+    number_of_power_cords: u8,
+    contained_elements: Vec<ContainedElement>,
+    sku_number: StringIndex,
+    strings: Vec<String>,
+}
+
+impl SystemEnclosure {
+    pub const TYPE_NUMBER: u8 = 3;
+
+    pub fn new(handle: u16) -> Self {
+        Self {
+            handle,
+            manufacturer: 0,
+            chassis_type: ChassisType::default(),
+            chassis_lock: false,
+            version: 0,
+            serial_number: 0,
+            asset_tag: 0,
+            boot_up_state: ChassisState::default(),
+            power_supply_state: ChassisState::default(),
+            thermal_state: ChassisState::default(),
+            security_status: ChassisSecurityStatus::default(),
+            oem_defined: 0,
+            // 0 means the height and the number of power cords are unspecified
+            height: 0,
+            number_of_power_cords: 0,
+            contained_elements: Vec::new(),
+            sku_number: 0,
+            strings: Vec::new(),
+        }
+    }
+
+    enclosure_strings!(manufacturer, version, serial_number, asset_tag, sku_number);
+
+    pub fn set_chassis_type(&mut self, chassis_type: ChassisType) {
+        self.chassis_type = chassis_type;
+    }
+
+    pub fn set_chassis_lock(&mut self, present: bool) {
+        self.chassis_lock = present;
+    }
+
+    pub fn set_boot_up_state(&mut self, state: ChassisState) {
+        self.boot_up_state = state;
+    }
+
+    pub fn set_power_supply_state(&mut self, state: ChassisState) {
+        self.power_supply_state = state;
+    }
+
+    pub fn set_thermal_state(&mut self, state: ChassisState) {
+        self.thermal_state = state;
+    }
+
+    pub fn set_security_status(&mut self, status: ChassisSecurityStatus) {
+        self.security_status = status;
+    }
+
+    pub fn set_oem_defined(&mut self, oem_defined: u32) {
+        self.oem_defined = oem_defined;
+    }
+
+    // Height in rack units (1U is 1.75 inches)
+    pub fn set_height(&mut self, units: u8) {
+        self.height = units;
+    }
+
+    pub fn set_number_of_power_cords(&mut self, cords: u8) {
+        self.number_of_power_cords = cords;
+    }
+
+    // The minimum can be 0-254 and the maximum 1-255, and the minimum can't exceed the maximum.
+    // The element isn't added if the length byte can't cover it.
+    pub fn add_contained_element(
+        &mut self,
+        element_type: ContainedElementType,
+        minimum: u8,
+        maximum: u8,
+    ) -> Result<(), SmbiosError> {
+        if minimum == 0xff || maximum == 0 || maximum < minimum {
+            return Err(SmbiosError::InvalidContainedElementRange { minimum, maximum });
+        }
+        if 0x16 + 3 * (self.contained_elements.len() + 1) > 0xff {
+            return Err(SmbiosError::StructureTooLarge);
+        }
+        self.contained_elements.push(ContainedElement {
+            element_type,
+            minimum,
+            maximum,
+        });
+        Ok(())
+    }
+}
+
+impl SmbiosStructure for SystemEnclosure {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        sink.byte(Self::TYPE_NUMBER);
+        sink.byte(
+            (0x16 + 3 * self.contained_elements.len())
+                .try_into()
+                .unwrap(),
+        );
+        sink.word(self.handle);
+        sink.byte(self.manufacturer);
+        sink.byte(self.chassis_type as u8 | if self.chassis_lock { 0x80 } else { 0 });
+        sink.byte(self.version);
+        sink.byte(self.serial_number);
+        sink.byte(self.asset_tag);
+        sink.byte(self.boot_up_state as u8);
+        sink.byte(self.power_supply_state as u8);
+        sink.byte(self.thermal_state as u8);
+        sink.byte(self.security_status as u8);
+        sink.dword(self.oem_defined);
+        sink.byte(self.height);
+        sink.byte(self.number_of_power_cords);
+        sink.byte(self.contained_elements.len().try_into().unwrap());
+        sink.byte(3);
+        for element in &self.contained_elements {
+            sink.byte(element.element_type.into());
+            sink.byte(element.minimum);
+            sink.byte(element.maximum);
+        }
+        sink.byte(self.sku_number);
+        for s in &self.strings {
+            sink.vec(s.as_bytes());
+            sink.byte(0);
+        }
+        sink.byte(0);
+        if self.strings.is_empty() {
+            sink.byte(0);
+        }
+    }
+
+    fn byte_length(&self) -> usize {
+        let strings: usize = self.strings.iter().map(|s| s.len() + 1).sum();
+        0x16 + 3 * self.contained_elements.len()
+            + strings
+            + if self.strings.is_empty() { 2 } else { 1 }
+    }
+}
+
+impl SmbiosTable for SystemEnclosure {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
+    }
+
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
+    }
+}
+
 // Type 4 SMBIOS table (Processor Information)
 simple_smbios_structure! {
     4,
@@ -133,7 +689,7 @@ simple_smbios_structure! {
         data: struct Type4Data {
             socket_designation: StringIndex,
             processor_type: ProcessorType,
-            processor_family: ProcessorFamily,
+            processor_family: ProcessorFamilyRaw,
             processor_manufacturer: StringIndex,
             processor_id: U64, // mvendorid for risc-v
             processor_version: StringIndex,
@@ -152,8 +708,8 @@ simple_smbios_structure! {
             core_count: u8,
             core_enabled: u8,
             thread_count: u8,
-            processor_characteristics: U16,
-            processor_family2: ProcessorFamily2,
+            processor_characteristics: RiscvProcessorCharacteristics1,
+            processor_family2: ProcessorFamily2Raw,
             core_count2: U16,
             core_enabled2: U16,
             thread_count2: U16,
@@ -163,6 +719,178 @@ simple_smbios_structure! {
 }
 static_assertions::const_assert!(size_of::<Type4Data>() == 0x32);
 
+// Bit 6 of the status field
+const SOCKET_POPULATED: u8 = 1 << 6;
+
+impl ProcessorInformation {
+    // Returns `None` if `xlen` has no matching RISC-V processor family
+    pub fn new_risc_v(handle: u16, xlen: Xlen) -> Option<Self> {
+        let family = match xlen {
+            Xlen::Xlen32 => ProcessorFamily2::RiscvRv32,
+            Xlen::Xlen64 => ProcessorFamily2::RiscvRv64,
+            Xlen::Xlen128 => ProcessorFamily2::RiscvRv128,
+            Xlen::Unsupported => return None,
+        };
+        let mut p = Self::new(handle);
+        p.data.processor_type = ProcessorType::CentralProcessor;
+        p.data.processor_family = ProcessorFamily::ObtainFrom2.into();
+        p.data.processor_family2 = family.into();
+        // Voltage isn't meaningful for RISC-V, so leave it as unknown
+        p.data.voltage = 0;
+        // RISC-V SoCs aren't socketed
+        p.data.processor_ugprade = ProcessorUpgrade::None;
+        Some(p)
+    }
+
+    // The processor ID of a RISC-V hart holds its mvendorid. This field is only 64 bits, unlike
+    // the 128-bit mvendorid in Type 44. The family fields are left alone, since they depend on
+    // the XLEN; `new_risc_v` sets them.
+    pub fn set_processor_id_riscv_mvendorid(&mut self, mvendorid: u64) {
+        self.data.processor_type = ProcessorType::CentralProcessor;
+        self.data.processor_id = mvendorid.into();
+    }
+
+    // `None` means the processor doesn't have that level of cache
+    pub fn set_cache_handles(
+        &mut self,
+        l1: Option<CacheHandle>,
+        l2: Option<CacheHandle>,
+        l3: Option<CacheHandle>,
+    ) {
+        self.data.l1_cache_handle = l1.unwrap_or(HANDLE_NO_CACHE).into();
+        self.data.l2_cache_handle = l2.unwrap_or(HANDLE_NO_CACHE).into();
+        self.data.l3_cache_handle = l3.unwrap_or(HANDLE_NO_CACHE).into();
+    }
+
+    pub fn set_no_caches(&mut self) {
+        self.set_cache_handles(None, None, None);
+    }
+
+    // Speeds are in MHz, with 0 meaning unknown. An unknown speed isn't checked against the
+    // other one.
+    pub fn set_speeds_mhz(&mut self, max: u16, current: u16) -> Result<(), SmbiosError> {
+        if max != 0 && current > max {
+            return Err(SmbiosError::SpeedRangeInvalid { max, current });
+        }
+        self.data.max_speed = max.into();
+        self.data.current_speed = current.into();
+        Ok(())
+    }
+
+    pub fn get_max_speed_mhz(&self) -> u16 {
+        self.data.max_speed.get()
+    }
+
+    pub fn set_max_speed_mhz(&mut self, mhz: u16) -> Result<(), SmbiosError> {
+        self.set_speeds_mhz(mhz, self.data.current_speed.get())
+    }
+
+    pub fn set_current_speed_mhz(&mut self, mhz: u16) -> Result<(), SmbiosError> {
+        self.set_speeds_mhz(self.data.max_speed.get(), mhz)
+    }
+
+    pub fn set_external_clock_mhz(&mut self, mhz: u16) {
+        self.data.external_clock = mhz.into();
+    }
+
+    // The CPU status of an empty socket is always unknown
+    pub fn set_socket_status(&mut self, populated: bool, cpu_status: CpuStatus) {
+        self.data.status = if populated {
+            SOCKET_POPULATED | cpu_status as u8
+        } else {
+            CpuStatus::Unknown as u8
+        };
+    }
+
+    pub fn is_populated(&self) -> bool {
+        self.data.status & SOCKET_POPULATED != 0
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.data.status & 0x7 == CpuStatus::Enabled as u8
+    }
+
+    pub fn set_upgrade(&mut self, upgrade: ProcessorUpgrade) {
+        self.data.processor_ugprade = upgrade;
+    }
+
+    // Returns a copy of `hart_info` that refers to this processor
+    pub fn link_to_hart(
+        &self,
+        hart_info: &RiscvProcessorAdditionalInformation,
+    ) -> RiscvProcessorAdditionalInformation {
+        let mut h = hart_info.clone();
+        h.data.referenced_handle = self.get_handle().into();
+        h
+    }
+
+    // A byte-wide family can't be the "obtain from Processor Family 2" indicator, and word-wide
+    // families 0xfffe and 0xffff are reserved
+    pub fn set_processor_family_value(
+        &mut self,
+        family: ProcessorFamilyValue,
+    ) -> Result<(), SmbiosError> {
+        match family {
+            ProcessorFamilyValue::Byte(f) if f == ProcessorFamily::ObtainFrom2 as u8 => {
+                Err(SmbiosError::InvalidProcessorFamily { family: f.into() })
+            }
+            ProcessorFamilyValue::Byte(f) => {
+                self.data.processor_family = ProcessorFamilyRaw(f);
+                Ok(())
+            }
+            ProcessorFamilyValue::Word(f @ 0xfffe..=0xffff) => {
+                Err(SmbiosError::InvalidProcessorFamily { family: f })
+            }
+            ProcessorFamilyValue::Word(f) => {
+                self.data.processor_family = ProcessorFamily::ObtainFrom2.into();
+                self.data.processor_family2 = ProcessorFamily2Raw(f.into());
+                Ok(())
+            }
+        }
+    }
+
+    // Counts above 254 set the byte-wide field to 0xff and are held in Core Count 2
+    pub fn get_core_count(&self) -> u16 {
+        match self.data.core_count {
+            0xff => self.data.core_count2.get(),
+            c => c.into(),
+        }
+    }
+
+    // Returns the processor family, regardless of which field holds it
+    pub fn get_effective_processor_family(&self) -> u16 {
+        match self.data.processor_family.0 {
+            f if f == ProcessorFamily::ObtainFrom2 as u8 => self.data.processor_family2.0.get(),
+            f => f.into(),
+        }
+    }
+
+    // On x86 the processor ID is CPUID leaf 1: EAX (signature) low, EDX (feature flags) high
+    pub fn set_processor_id_from_x86_cpuid(&mut self, cpuid_eax1: u32, cpuid_edx1: u32) {
+        self.data.processor_id = ((u64::from(cpuid_edx1) << 32) | u64::from(cpuid_eax1)).into();
+    }
+
+    pub fn get_x86_cpuid_signature(&self) -> u32 {
+        self.data.processor_id.get() as u32
+    }
+}
+
+impl fmt::Display for ProcessorInformation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Type 4 (Processor Information): socket=\"{}\", manufacturer=\"{}\", \
+             version=\"{}\", family={:#x}, max_speed={} MHz, cores={}",
+            self.get_string(self.data.socket_designation),
+            self.get_string(self.data.processor_manufacturer),
+            self.get_string(self.data.processor_version),
+            self.get_effective_processor_family(),
+            self.data.max_speed.get(),
+            self.get_core_count()
+        )
+    }
+}
+
 // Type 7 SMBIOS table (Cache Information)
 simple_smbios_structure! {
     7,
@@ -185,37 +913,195 @@ simple_smbios_structure! {
 }
 static_assertions::const_assert!(size_of::<Type7Data>() == 0x1b);
 
-// Type 9 SMBIOS table (System Slots)
-// Note: This does not include support for peer groups
-simple_smbios_structure! {
-    9,
-    struct SystemSlots {
-        data: struct Type9Data {
-            slot_designation: StringIndex,
-            slot_type: SlotType,
-            slot_data_bus_width: SlotWidth,
-            current_usage: CurrentUsage,
-            slot_length: SlotLength,
-            slot_id: U16,
-            slot_characteristics1: u8,
-            slot_characteristics2: u8,
-            segment_group_number: U16,
+impl CacheInformation {
+    // An enabled, internal, write-back cache at the given level (1-8)
+    fn new_cache(handle: u16, level: u16, cache_type: SystemCacheType) -> Self {
+        let mut c = Self::new(handle);
+        let mut config = CacheConfiguration(0);
+        config.set_level(level - 1);
+        config.set_location(0); // internal
+        config.set_enabled(true);
+        config.set_op_mode(1); // write back
+        c.data.cache_configuration = config;
+        c.data.supported_sram_type = SramType::Unknown;
+        c.data.current_sram_type = SramType::Unknown;
+        c.data.error_correction_type = EccType::Unknown;
+        c.data.system_cache_type = cache_type;
+        c
+    }
+
+    pub fn new_l1_instruction(handle: u16) -> Self {
+        Self::new_cache(handle, 1, SystemCacheType::Instruction)
+    }
+
+    pub fn new_l1_data(handle: u16) -> Self {
+        Self::new_cache(handle, 1, SystemCacheType::Data)
+    }
+
+    pub fn new_l2_unified(handle: u16) -> Self {
+        Self::new_cache(handle, 2, SystemCacheType::Unified)
+    }
+
+    pub fn new_l3_unified(handle: u16) -> Self {
+        Self::new_cache(handle, 3, SystemCacheType::Unified)
+    }
+
+    // RISC-V cores usually have separate L1 instruction and data caches, and a unified L2, all
+    // built from synchronous SRAM without ECC. Way counts the spec has no value for are shown
+    // as `Associativity::Other`.
+    fn new_risc_v_cache(
+        handle: u16,
+        level: u16,
+        cache_type: SystemCacheType,
+        size_kb: u32,
+        ways: u8,
+    ) -> Self {
+        let mut c = Self::new_cache(handle, level, cache_type);
+        c.data.supported_sram_type = SramType::Synchronous;
+        c.data.current_sram_type = SramType::Synchronous;
+        c.data.error_correction_type = EccType::None;
+        c.data.associativity =
+            Associativity::from_ways(u32::from(ways)).unwrap_or(Associativity::Other);
+        let bytes = u64::from(size_kb) << 10;
+        c.set_installed_cache_size_bytes(bytes);
+        c.set_maximum_cache_size_bytes(bytes);
+        c
+    }
+
+    pub fn new_risc_v_l1_instruction(handle: u16, size_kb: u32, ways: u8) -> Self {
+        Self::new_risc_v_cache(handle, 1, SystemCacheType::Instruction, size_kb, ways)
+    }
+
+    pub fn new_risc_v_l1_data(handle: u16, size_kb: u32, ways: u8) -> Self {
+        Self::new_risc_v_cache(handle, 1, SystemCacheType::Data, size_kb, ways)
+    }
+
+    pub fn new_risc_v_l2_unified(handle: u16, size_kb: u32, ways: u8) -> Self {
+        Self::new_risc_v_cache(handle, 2, SystemCacheType::Unified, size_kb, ways)
+    }
+
+    // Cache sizes are in 1 KiB units, or 64 KiB units if the granularity bit (the top bit) is set.
+    // Sizes too large for the 16-bit field (2 GiB and above) set it to all ones and are only in
+    // the 32-bit field; otherwise the 32-bit field holds the same value. Sizes beyond the largest
+    // the 32-bit field can hold (just under 128 TiB) are saturated to it.
+    fn encode_cache_size(bytes: u64) -> (U16, U32) {
+        let kib = bytes.div_ceil(1024);
+        let units_64k = kib.div_ceil(64);
+        let size2 = match u32::try_from(kib) {
+            Ok(kib) if kib <= 0x7fff => return ((kib as u16).into(), kib.into()),
+            Ok(kib) if kib <= 0x7fff_ffff && units_64k > 0x7fff => kib,
+            _ => 0x8000_0000 | units_64k.min(0x7fff_ffff) as u32,
+        };
+        match u16::try_from(units_64k) {
+            Ok(units) if units <= 0x7fff => ((0x8000 | units).into(), size2.into()),
+            _ => (0xffff.into(), size2.into()),
+        }
+    }
+
+    // The 32-bit field takes precedence when it's set; structures from before SMBIOS 3.1 only
+    // have the 16-bit one
+    fn decode_cache_size(size: U16, size2: U32) -> u64 {
+        let (units, granular) = match size2.get() {
+            0 => (u64::from(size.get() & 0x7fff), size.get() & 0x8000 != 0),
+            s => (u64::from(s & 0x7fff_ffff), s & 0x8000_0000 != 0),
+        };
+        if granular {
+            units << 16
+        } else {
+            units << 10
+        }
+    }
+
+    pub fn set_installed_cache_size_bytes(&mut self, bytes: u64) {
+        (self.data.installed_size, self.data.installed_cache_size2) =
+            Self::encode_cache_size(bytes);
+    }
+
+    pub fn set_maximum_cache_size_bytes(&mut self, bytes: u64) {
+        (self.data.maximum_cache_size, self.data.maximum_cache_size2) =
+            Self::encode_cache_size(bytes);
+    }
+
+    pub fn get_installed_cache_size_bytes(&self) -> u64 {
+        Self::decode_cache_size(self.data.installed_size, self.data.installed_cache_size2)
+    }
+
+    pub fn get_maximum_cache_size_bytes(&self) -> u64 {
+        Self::decode_cache_size(self.data.maximum_cache_size, self.data.maximum_cache_size2)
+    }
+}
+
+impl fmt::Display for CacheInformation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let config = self.data.cache_configuration;
+        write!(
+            f,
+            "Type 7 (Cache Information): socket=\"{}\", level=L{}, type={:?}, \
+             installed_size={} KB",
+            self.get_string(self.data.socket_designation),
+            config.level() + 1,
+            self.data.system_cache_type,
+            self.get_installed_cache_size_bytes() >> 10
+        )
+    }
+}
+
+// Type 9 SMBIOS table (System Slots)
+// Note: This does not include support for peer groups
+simple_smbios_structure! {
+    9,
+    struct SystemSlots {
+        data: struct Type9Data {
+            slot_designation: StringIndex,
+            slot_type: SlotType,
+            slot_data_bus_width: SlotWidth,
+            current_usage: CurrentUsage,
+            slot_length: SlotLength,
+            slot_id: U16,
+            slot_characteristics1: u8,
+            slot_characteristics2: u8,
+            segment_group_number: U16,
             bus_number: u8,
             devfn: u8,
             data_bus_width: u8,
             peer_group_count: u8,
+            slot_information: u8,
+            slot_physical_width: SlotWidth,
+            slot_pitch: SlotPitch,
+            slot_height: SlotHeight,
         }
     }
 }
-static_assertions::const_assert!(size_of::<Type9Data>() == 0x13);
+static_assertions::const_assert!(size_of::<Type9Data>() == 0x18);
+
+impl SystemSlots {
+    pub fn set_slot_characteristics1_flags(&mut self, flags: SlotCharacteristics1) {
+        self.data.slot_characteristics1 = flags.bits();
+    }
+
+    pub fn set_slot_characteristics2_flags(&mut self, flags: SlotCharacteristics2) {
+        self.data.slot_characteristics2 = flags.bits();
+    }
+
+    pub fn add_slot_characteristics2_flags(&mut self, flags: SlotCharacteristics2) {
+        self.data.slot_characteristics2 |= flags.bits();
+    }
+
+    pub fn set_slot_pitch_hundredths_mm(&mut self, pitch: u16) {
+        self.data.slot_pitch = SlotPitch::from_hundredths_mm(pitch);
+    }
+}
 
 // Type 11 SMBIOS table (OEM Strings)
+#[derive(Clone, Debug, Default)]
 pub struct OemStrings {
     handle: u16,
     strings: Vec<String>,
 }
 
 impl OemStrings {
+    pub const TYPE_NUMBER: u8 = 11;
+
     pub fn new(handle: u16) -> Self {
         Self {
             handle,
@@ -223,22 +1109,261 @@ impl OemStrings {
         }
     }
 
+    // The length byte only covers the formatted section, so the strings themselves aren't
+    // limited in size. Their count is held in a byte, though.
+    pub const MAX_STRINGS: usize = 255;
+
+    // Panics if the string is rejected by `add_string_checked`
     pub fn add_string(&mut self, s: &str) {
+        self.add_string_checked(s).unwrap();
+    }
+
+    // An empty string would end the string set early, so it's rejected along with embedded
+    // nulls
+    pub fn add_string_checked(&mut self, s: &str) -> Result<(), SmbiosError> {
+        if s.is_empty() || !s.is_ascii() || s.contains('\0') {
+            return Err(SmbiosError::InvalidString);
+        }
+        if self.strings.len() == Self::MAX_STRINGS {
+            return Err(SmbiosError::TooManyStrings {
+                limit: Self::MAX_STRINGS,
+            });
+        }
         self.strings.push(s.into());
+        Ok(())
+    }
+
+    // Total size of the strings, including their null terminators
+    pub fn total_string_bytes(&self) -> usize {
+        self.strings.iter().map(|s| s.len() + 1).sum()
     }
 }
 
 impl SmbiosStructure for OemStrings {
     fn serialize(&self, sink: &mut dyn Sink) {
-        sink.byte(11);
+        sink.byte(Self::TYPE_NUMBER);
         sink.byte(5);
         sink.word(self.handle);
-        sink.byte(self.strings.len().try_into().unwrap());
+        sink.byte(self.strings.len() as u8);
         for s in &self.strings {
             sink.vec(s.as_bytes());
             sink.byte(0);
         }
         sink.byte(0);
+        if self.strings.is_empty() {
+            sink.byte(0);
+        }
+    }
+
+    fn byte_length(&self) -> usize {
+        5 + self.total_string_bytes() + if self.strings.is_empty() { 2 } else { 1 }
+    }
+}
+
+impl SmbiosTable for OemStrings {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
+    }
+
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
+    }
+}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone, Default, Debug, AsBytes)]
+pub struct GroupAssociationItem {
+    item_type: u8,
+    item_handle: StructureHandle,
+}
+
+impl GroupAssociationItem {
+    pub fn new(item_type: u8, item_handle: u16) -> Self {
+        Self {
+            item_type,
+            item_handle: item_handle.into(),
+        }
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.item_handle = remap(self.item_handle.get()).into();
+    }
+}
+
+// Type 14 SMBIOS table (Group Associations)
+// Each item is 3 bytes (type and handle), so the structure length depends on the number of
+// items.
+simple_smbios_structure! {
+    14,
+    struct GroupAssociations {
+        data: struct Type14Data {
+            group_name: StringIndex,
+        },
+        item: [GroupAssociationItem; variable],
+    }
+}
+static_assertions::const_assert!(size_of::<GroupAssociationItem>() == 3);
+
+#[derive(Copy, Clone, Debug)]
+pub struct EventTypeDescriptor {
+    pub event_type: u8,
+    pub data_format_type: u8,
+}
+
+// Type 15 SMBIOS table (System Event Log)
+// The fixed part is 0x17 bytes, followed by the supported event type descriptors.
+#[derive(Clone, Debug)]
+pub struct SystemEventLog {
+    handle: u16,
+    log_area_length: u16,
+    log_header_start_offset: u16,
+    log_data_start_offset: u16,
+    access_method: LogAccessMethod,
+    log_status: LogStatus,
+    log_change_token: u32,
+    access_method_address: u32,
+    log_header_format: LogHeaderFormat,
+    descriptors: Vec<EventTypeDescriptor>,
+}
+
+impl SystemEventLog {
+    pub const TYPE_NUMBER: u8 = 15;
+
+    fn new(handle: u16, access_method: LogAccessMethod, access_method_address: u32) -> Self {
+        Self {
+            handle,
+            log_area_length: 0,
+            log_header_start_offset: 0,
+            log_data_start_offset: 0,
+            access_method,
+            log_status: LogStatus::empty(),
+            log_change_token: 0,
+            access_method_address,
+            log_header_format: LogHeaderFormat::default(),
+            descriptors: Vec::new(),
+        }
+    }
+
+    // For the indexed I/O methods, the address holds the index and data port addresses
+    pub fn new_indexed_io(
+        handle: u16,
+        access_method: LogAccessMethod,
+        index_port: u16,
+        data_port: u16,
+    ) -> Result<Self, SmbiosError> {
+        match access_method {
+            LogAccessMethod::IndexedIo1x8Bit
+            | LogAccessMethod::IndexedIo2x8Bit
+            | LogAccessMethod::IndexedIo1x16Bit => Ok(Self::new(
+                handle,
+                access_method,
+                u32::from(index_port) | (u32::from(data_port) << 16),
+            )),
+            _ => Err(SmbiosError::InvalidLogAccessMethod {
+                access_method: access_method as u8,
+            }),
+        }
+    }
+
+    pub fn new_memory_mapped(handle: u16, physical_address: u32) -> Self {
+        Self::new(handle, LogAccessMethod::MemoryMapped32Bit, physical_address)
+    }
+
+    pub fn new_gpnv(handle: u16, gpnv_handle: u16) -> Self {
+        Self::new(
+            handle,
+            LogAccessMethod::GeneralPurposeNonVolatile,
+            gpnv_handle.into(),
+        )
+    }
+
+    // Offsets are relative to the start of the access method address
+    pub fn set_log_area(&mut self, length: u16, header_start_offset: u16, data_start_offset: u16) {
+        self.log_area_length = length;
+        self.log_header_start_offset = header_start_offset;
+        self.log_data_start_offset = data_start_offset;
+    }
+
+    pub fn set_log_status(&mut self, status: LogStatus) {
+        self.log_status = status;
+    }
+
+    pub fn set_log_change_token(&mut self, token: u32) {
+        self.log_change_token = token;
+    }
+
+    pub fn set_log_header_format(&mut self, format: LogHeaderFormat) {
+        self.log_header_format = format;
+    }
+
+    // The descriptor isn't added if the length byte can't cover it
+    pub fn add_event_type_descriptor(
+        &mut self,
+        event_type: u8,
+        data_format_type: u8,
+    ) -> Result<(), SmbiosError> {
+        if 0x17 + 2 * (self.descriptors.len() + 1) > 0xff {
+            return Err(SmbiosError::StructureTooLarge);
+        }
+        self.descriptors.push(EventTypeDescriptor {
+            event_type,
+            data_format_type,
+        });
+        Ok(())
+    }
+}
+
+impl SmbiosStructure for SystemEventLog {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        sink.byte(Self::TYPE_NUMBER);
+        sink.byte((0x17 + 2 * self.descriptors.len()).try_into().unwrap());
+        sink.word(self.handle);
+        sink.word(self.log_area_length);
+        sink.word(self.log_header_start_offset);
+        sink.word(self.log_data_start_offset);
+        sink.byte(self.access_method as u8);
+        sink.byte(self.log_status.bits());
+        sink.dword(self.log_change_token);
+        sink.dword(self.access_method_address);
+        sink.byte(self.log_header_format as u8);
+        sink.byte(self.descriptors.len().try_into().unwrap());
+        sink.byte(2);
+        for descriptor in &self.descriptors {
+            sink.byte(descriptor.event_type);
+            sink.byte(descriptor.data_format_type);
+        }
+        sink.byte(0);
+        sink.byte(0);
+    }
+
+    fn byte_length(&self) -> usize {
+        0x17 + 2 * self.descriptors.len() + 2
+    }
+}
+
+impl SmbiosTable for SystemEventLog {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
+    }
+
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
     }
 }
 
@@ -270,8 +1395,53 @@ impl PhysicalMemoryArray {
             self.data.extended_maximum_capacity = 0.into();
         }
     }
+
+    pub fn set_error_information(&mut self, handle: Option<MemoryErrorHandle>) {
+        self.data.error_information_handle = handle.unwrap_or(HANDLE_NOT_PROVIDED).into();
+    }
+
+    pub fn set_error_information_unsupported(&mut self) {
+        self.data.error_information_handle = HANDLE_NOT_SUPPORTED.into();
+    }
+
+    pub fn get_memory_error_correction(&self) -> ErrorCorrectionType {
+        self.data.memory_error_correction
+    }
+
+    pub fn get_number_of_memory_devices(&self) -> u16 {
+        self.data.number_of_memory_devices.get()
+    }
+
+    pub fn get_memory_capacity(&self) -> u64 {
+        match self.data.maximum_capacity.get() {
+            0x8000_0000 => self.data.extended_maximum_capacity.get(),
+            cap_kb => u64::from(cap_kb) * 1024,
+        }
+    }
 }
 
+impl fmt::Display for PhysicalMemoryArray {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Type 16 (Physical Memory Array): location={:?}, use={:?}, ecc={:?}, \
+             capacity={} MB, devices={}",
+            self.data.location,
+            self.data.array_use,
+            self.data.memory_error_correction,
+            to_mb(self.get_memory_capacity()),
+            self.data.number_of_memory_devices.get()
+        )
+    }
+}
+
+// Common memory module voltages, in millivolts
+pub const MEMORY_VOLTAGE_1500MV: u16 = 1500; // DDR3
+pub const MEMORY_VOLTAGE_1350MV: u16 = 1350; // DDR3L
+pub const MEMORY_VOLTAGE_1200MV: u16 = 1200; // DDR4
+pub const MEMORY_VOLTAGE_1100MV: u16 = 1100; // DDR5
+pub const MEMORY_VOLTAGE_1050MV: u16 = 1050; // DDR5 (low voltage)
+
 // Type 17 SMBIOS table (Memory Device)
 simple_smbios_structure! {
     17,
@@ -321,7 +1491,185 @@ simple_smbios_structure! {
 }
 static_assertions::const_assert!(size_of::<Type17Data>() == 0x64);
 
+// Decode the Type 17 size and extended size fields into bytes; 0 means no device is installed
+fn decode_memory_device_size(size: u16, extended_size: u32) -> Option<u64> {
+    match size {
+        0xffff => None,
+        0x7fff => Some(mb(u64::from(extended_size & 0x7fff_ffff))),
+        size if size & 0x8000 != 0 => Some(u64::from(size & 0x7fff) * 1024),
+        size => Some(mb(u64::from(size))),
+    }
+}
+
+// The fields of a DDR SPD EEPROM which are needed to describe a module in a Type 17 structure,
+// already decoded from the SPD encoding
+#[derive(Clone, Debug, Default)]
+pub struct SpdData {
+    pub memory_type: MemoryType,
+    pub size_mb: u32,
+    pub speed_mts: u32,
+    // Continuation codes and ID code, as they appear in SPD
+    pub manufacturer_jedec: [u8; 2],
+    // Padded with spaces
+    pub part_number: [u8; 18],
+    pub serial_number: [u8; 4],
+    pub form_factor: FormFactor,
+    pub total_width_bits: u16,
+    pub data_width_bits: u16,
+    pub ranks: u8,
+}
+
 impl MemoryDevice {
+    // The serial number is shown as hex, in the order the bytes appear in SPD
+    pub fn apply_spd_data(&mut self, spd: &SpdData) {
+        self.data.memory_type = spd.memory_type;
+        self.data.form_factor = spd.form_factor;
+        self.set_memory_size(Some(mb(u64::from(spd.size_mb))));
+        self.set_speed_mts(spd.speed_mts);
+        self.data.total_width = spd.total_width_bits.into();
+        self.data.data_width = spd.data_width_bits.into();
+        let [continuation_codes, id] = spd.manufacturer_jedec;
+        self.set_module_manufacturer(JedecManufacturerId::from_spd_bytes(continuation_codes, id));
+        self.set_serial_number(&alloc::format!(
+            "{:08X}",
+            u32::from_be_bytes(spd.serial_number)
+        ));
+        self.set_module_part_number_bytes(&spd.part_number);
+        // Bits 3:0 of the attributes are the rank, with 0 meaning unknown
+        self.data.attributes = (self.data.attributes & 0xf0) | (spd.ranks & 0xf);
+    }
+
+    pub fn new_with_array(
+        handle: u16,
+        array_handle: MemoryArrayHandle,
+        device_locator: &str,
+        bank_locator: &str,
+    ) -> Self {
+        let mut m = Self::new(handle);
+        m.data.physical_memory_array_handle = array_handle.into();
+        m.set_locators(device_locator, bank_locator);
+        m
+    }
+
+    // The SPD part number is 18 (DDR4) or 30 (DDR5) bytes of ASCII, padded with spaces
+    pub fn set_module_part_number_bytes(&mut self, part: &[u8]) {
+        self.set_part_number(trim_smbios_string(&String::from_utf8_lossy(part)));
+    }
+
+    pub fn set_manufacturer_bytes(&mut self, bytes: &[u8; 8]) {
+        self.set_manufacturer(trim_smbios_string(&String::from_utf8_lossy(bytes)));
+    }
+
+    // An empty socket has a size of 0; everything else about it is unknown
+    pub fn new_empty_slot(handle: u16, array_handle: MemoryArrayHandle, locator: &str) -> Self {
+        let mut m = Self::new(handle);
+        m.data.physical_memory_array_handle = array_handle.into();
+        m.set_device_locator(locator);
+        m.set_memory_size(Some(0));
+        m.set_module_dimensions_unknown();
+        m.set_error_information(None);
+        m.data.form_factor = FormFactor::Unknown;
+        m.data.memory_type = MemoryType::Unknown;
+        m.data.type_detail = TypeDetail::Unknown.bits().into();
+        m.data.memory_technology = MemoryTechnology::Unknown;
+        m.data.memory_operating_mode = OperatingMode::Unknown.bits().into();
+        m
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_present(
+        handle: u16,
+        array_handle: MemoryArrayHandle,
+        size_bytes: u64,
+        ty: MemoryType,
+        ff: FormFactor,
+        locator: &str,
+        bank: &str,
+        speed_mts: u32,
+    ) -> Self {
+        let mut m = Self::new_with_array(handle, array_handle, locator, bank);
+        m.set_memory_size(Some(size_bytes));
+        m.data.memory_type = ty;
+        m.data.form_factor = ff;
+        m.set_speed_mts(speed_mts);
+        m
+    }
+
+    // Speeds of 65535 MT/s and above go in the extended field, with the 16-bit field all ones
+    fn encode_speed(speed_mts: u32) -> (U16, U32) {
+        match u16::try_from(speed_mts) {
+            Ok(speed) if speed < 0xffff => (speed.into(), 0.into()),
+            _ => (0xffff.into(), speed_mts.into()),
+        }
+    }
+
+    fn decode_speed(speed: U16, extended_speed: U32) -> u32 {
+        match speed.get() {
+            0xffff => extended_speed.get(),
+            speed => speed.into(),
+        }
+    }
+
+    pub fn set_speed_mts(&mut self, speed_mts: u32) {
+        (self.data.speed, self.data.extended_speed) = Self::encode_speed(speed_mts);
+    }
+
+    // 0 means unknown
+    pub fn get_speed_mts(&self) -> u32 {
+        Self::decode_speed(self.data.speed, self.data.extended_speed)
+    }
+
+    pub fn set_configured_speed_mts(&mut self, speed_mts: u32) {
+        (
+            self.data.configured_memory_speed,
+            self.data.extended_configured_memory_speed,
+        ) = Self::encode_speed(speed_mts);
+    }
+
+    // Systems whose banks have no designation leave the bank locator without a string
+    pub fn set_bank_locator_empty(&mut self) {
+        self.clear_bank_locator();
+    }
+
+    pub fn set_locators(&mut self, device: &str, bank: &str) {
+        self.set_device_locator(device);
+        self.set_bank_locator(bank);
+    }
+
+    pub fn set_locators_from_slot_index(&mut self, socket: u8, channel: u8, slot: u8) {
+        self.set_locators_from_slot_index_with_format(
+            socket,
+            channel,
+            slot,
+            LocatorFormat::Generic,
+        );
+    }
+
+    // Channels past 'Z' can't be shown as a single letter, so they fall back to the generic
+    // format
+    pub fn set_locators_from_slot_index_with_format(
+        &mut self,
+        socket: u8,
+        channel: u8,
+        slot: u8,
+        format: LocatorFormat,
+    ) {
+        let (device, bank) = match format {
+            LocatorFormat::ChannelLetters if channel < 26 => {
+                let letter = char::from(b'A' + channel);
+                (
+                    alloc::format!("CPU{socket}_DIMM_{letter}{slot}"),
+                    alloc::format!("P{socket}_Node{socket}_Channel{channel}_Dimm{slot}"),
+                )
+            }
+            _ => (
+                alloc::format!("P{socket}_D{channel}{slot}"),
+                alloc::format!("NODE {socket} CHANNEL {channel} DIMM {slot}"),
+            ),
+        };
+        self.set_locators(&device, &bank);
+    }
+
     pub fn set_memory_size(&mut self, size: Option<u64>) {
         match size {
             Some(size) => {
@@ -340,71 +1688,609 @@ impl MemoryDevice {
             None => self.data.size = 0xffff.into(),
         }
     }
-}
 
-// Type 19 SMBIOS table (Memory Array Mapped Address)
-simple_smbios_structure! {
-    19,
-    struct MemoryArrayMappedAddress {
-        data: struct Type19Data {
-            starting_address: U32,
-            ending_address: U32,
-            memory_array_handle: StructureHandle,
-            partition_width: u8,
-            extended_starting_address: U64,
-            extended_ending_address: U64,
-        }
+    // Returns the size of the device in bytes, or None if it's unknown
+    pub fn get_memory_size(&self) -> Option<u64> {
+        decode_memory_device_size(self.data.size.get(), self.data.extended_size.get())
     }
-}
-static_assertions::const_assert!(size_of::<Type19Data>() == 0x1f);
 
-impl MemoryArrayMappedAddress {
-    pub fn set_address_range(&mut self, starting: u64, ending: u64) {
-        self.data.starting_address = u32::MAX.into();
-        self.data.ending_address = u32::MAX.into();
-        self.data.extended_starting_address = starting.into();
-        self.data.extended_ending_address = ending.into();
+    pub fn get_physical_memory_array_handle(&self) -> MemoryArrayHandle {
+        self.data.physical_memory_array_handle.get()
     }
-}
 
-// Type 20 SMBIOS table (Memory Device Mapped Address)
-simple_smbios_structure! {
-    20,
-    struct MemoryDeviceMappedAddress {
-        data: struct Type20Data {
-            starting_address: U32,
-            ending_address: U32,
-            memory_device_handle: StructureHandle,
-            memory_array_mapped_address_handle: StructureHandle,
-            partition_row_position: PartitionRowPosition,
-            interleave_position: u8,
-            interleaved_data_depth: u8,
-            extended_starting_address: U64,
-            extended_ending_address: U64,
+    // Widens `total_width` to cover the check bits of the array's error correction. Other
+    // correction types, and devices whose data width is unknown (such as empty slots), are left
+    // alone. Returns whether the width was changed.
+    pub fn set_ecc_width_for(&mut self, ecc: ErrorCorrectionType) -> bool {
+        let ecc_bits = match ecc {
+            ErrorCorrectionType::SingleBitEcc => 8,
+            ErrorCorrectionType::MultiBitEcc => 16,
+            _ => return false,
+        };
+        let total_width = match self.data.data_width.get() {
+            0 | 0xffff => None,
+            data_width => data_width.checked_add(ecc_bits),
+        };
+        match total_width {
+            Some(width) => {
+                self.data.total_width = width.into();
+                true
+            }
+            None => false,
         }
     }
-}
-static_assertions::const_assert!(size_of::<Type20Data>() == 0x23);
 
-impl MemoryDeviceMappedAddress {
-    pub fn set_address_range(&mut self, starting: u64, ending: u64) {
-        self.data.starting_address = u32::MAX.into();
-        self.data.ending_address = u32::MAX.into();
-        self.data.extended_starting_address = starting.into();
-        self.data.extended_ending_address = ending.into();
+    // `total_width` includes any ECC bits, `data_width` does not. Both widths are left unchanged
+    // if the total doesn't fit below the 0xffff "unknown" value.
+    pub fn set_module_dimensions(
+        &mut self,
+        data_width_bits: u16,
+        ecc_bits: u16,
+    ) -> Result<(), SmbiosError> {
+        match data_width_bits.checked_add(ecc_bits) {
+            Some(total_width) if total_width != 0xffff => {
+                self.data.data_width = data_width_bits.into();
+                self.data.total_width = total_width.into();
+                Ok(())
+            }
+            _ => Err(SmbiosError::ModuleWidthInvalid {
+                data_width: data_width_bits,
+                ecc: ecc_bits,
+            }),
+        }
     }
-}
 
-// Type 32 SMBIOS table (System Boot Information)
-#[derive(Debug, Default)]
-pub struct SystemBootInformation<'a> {
-    handle: u16,
-    status: BootStatus<'a>,
-}
+    pub fn set_error_information(&mut self, handle: Option<MemoryErrorHandle>) {
+        self.data.error_information_handle = handle.unwrap_or(HANDLE_NOT_PROVIDED).into();
+    }
+
+    pub fn set_error_information_unsupported(&mut self) {
+        self.data.error_information_handle = HANDLE_NOT_SUPPORTED.into();
+    }
+
+    pub fn set_module_dimensions_unknown(&mut self) {
+        self.data.data_width = 0xffff.into();
+        self.data.total_width = 0xffff.into();
+    }
+
+    pub fn set_data_width_bits(&mut self, data_width_bits: u16) {
+        self.data.data_width = data_width_bits.into();
+    }
+
+    // Memory that is soldered to the board has no bank, and isn't part of a set
+    pub fn set_as_soldered_memory(&mut self, size_bytes: u64, memory_type: MemoryType) {
+        self.set_memory_size(Some(size_bytes));
+        self.data.memory_type = memory_type;
+        self.data.form_factor = FormFactor::RowOfChips;
+        self.clear_bank_locator();
+        self.data.device_set = 0;
+    }
+
+    // For the NVDIMM region sizes, u64::MAX means unknown and 0 means the region isn't present
+    pub fn set_nvdimm_p_sizes(&mut self, non_volatile_bytes: u64, volatile_bytes: u64) {
+        self.data.non_volatile_size = non_volatile_bytes.into();
+        self.data.volatile_size = volatile_bytes.into();
+    }
+
+    pub fn set_nvdimm_non_volatile_size(
+        &mut self,
+        size: NvdimmSizeValue,
+    ) -> Result<(), InvalidNvdimmSize> {
+        self.data.non_volatile_size = u64::try_from(size)?.into();
+        Ok(())
+    }
+
+    pub fn set_nvdimm_volatile_size(
+        &mut self,
+        size: NvdimmSizeValue,
+    ) -> Result<(), InvalidNvdimmSize> {
+        self.data.volatile_size = u64::try_from(size)?.into();
+        Ok(())
+    }
+
+    pub fn set_nvdimm_cache_size(
+        &mut self,
+        size: NvdimmSizeValue,
+    ) -> Result<(), InvalidNvdimmSize> {
+        self.data.cache_size = u64::try_from(size)?.into();
+        Ok(())
+    }
+
+    pub fn set_nvdimm_logical_size(
+        &mut self,
+        size: NvdimmSizeValue,
+    ) -> Result<(), InvalidNvdimmSize> {
+        self.data.logical_size = u64::try_from(size)?.into();
+        Ok(())
+    }
+
+    pub fn get_nvdimm_non_volatile_size(&self) -> NvdimmSizeValue {
+        NvdimmSizeValue::from_u64(self.data.non_volatile_size.get())
+    }
+
+    pub fn get_nvdimm_volatile_size(&self) -> NvdimmSizeValue {
+        NvdimmSizeValue::from_u64(self.data.volatile_size.get())
+    }
+
+    pub fn get_nvdimm_cache_size(&self) -> NvdimmSizeValue {
+        NvdimmSizeValue::from_u64(self.data.cache_size.get())
+    }
+
+    pub fn get_nvdimm_logical_size(&self) -> NvdimmSizeValue {
+        NvdimmSizeValue::from_u64(self.data.logical_size.get())
+    }
+
+    pub fn set_type_detail_flags(&mut self, flags: TypeDetail) {
+        self.data.type_detail = flags.bits().into();
+    }
+
+    pub fn set_type_detail_rdimm(&mut self) {
+        self.set_type_detail_flags(TypeDetail::RDIMM);
+    }
+
+    pub fn set_type_detail_udimm(&mut self) {
+        self.set_type_detail_flags(TypeDetail::UDIMM);
+    }
+
+    pub fn set_type_detail_lrdimm(&mut self) {
+        self.set_type_detail_flags(TypeDetail::LRDIMM);
+    }
+
+    pub fn set_type_detail_sodimm(&mut self) {
+        self.set_type_detail_flags(TypeDetail::SODIMM);
+    }
+
+    pub fn set_type_detail_nvdimm(&mut self) {
+        self.set_type_detail_flags(TypeDetail::NVDIMM);
+    }
+
+    pub fn set_type_detail_hbm(&mut self) {
+        self.set_type_detail_flags(TypeDetail::HBM);
+    }
+
+    // A best guess at the type detail from the memory type alone: DDR5 is assumed to be on an
+    // RDIMM, and LPDDR is always unbuffered
+    pub fn set_type_detail_for_memory_type(&mut self, ty: MemoryType) {
+        self.set_type_detail_flags(match ty {
+            MemoryType::Ddr5 => TypeDetail::DDR5_RDIMM,
+            MemoryType::Lpddr
+            | MemoryType::Lpddr2
+            | MemoryType::Lpddr3
+            | MemoryType::Lpddr4
+            | MemoryType::Lpddr5
+            | MemoryType::Lpddr5x => TypeDetail::Synchronous | TypeDetail::Unbuffered,
+            _ => TypeDetail::Unknown,
+        });
+    }
+
+    // Sets the technology along with the operating modes it supports
+    pub fn set_technology(&mut self, tech: MemoryTechnology) {
+        use OperatingMode as Mode;
+        let mode = match tech {
+            MemoryTechnology::Other => Mode::Other,
+            MemoryTechnology::Dram | MemoryTechnology::Hbm | MemoryTechnology::Hbm2 => {
+                Mode::Volatile
+            }
+            MemoryTechnology::NvdimmN => Mode::ByteAccessiblePersistent,
+            MemoryTechnology::NvdimmF => Mode::BlockAccessiblePersistent,
+            MemoryTechnology::NvdimmP => {
+                Mode::ByteAccessiblePersistent | Mode::BlockAccessiblePersistent
+            }
+            MemoryTechnology::IntelOptane => Mode::ByteAccessiblePersistent | Mode::Volatile,
+            MemoryTechnology::Unknown | MemoryTechnology::Mram => Mode::Unknown,
+        };
+        self.data.memory_technology = tech;
+        self.data.memory_operating_mode = mode.bits().into();
+    }
+
+    // `volatile_fraction` is the percentage of the device that is volatile. Nothing is changed
+    // if it's over 100.
+    pub fn set_as_nvdimm_p(
+        &mut self,
+        total_bytes: u64,
+        volatile_fraction: u64,
+    ) -> Result<(), SmbiosError> {
+        if volatile_fraction > 100 {
+            return Err(SmbiosError::PercentageInvalid {
+                percent: volatile_fraction,
+            });
+        }
+        let volatile_bytes = (u128::from(total_bytes) * u128::from(volatile_fraction) / 100) as u64;
+        self.set_technology(MemoryTechnology::NvdimmP);
+        self.data.form_factor = FormFactor::Dimm;
+        self.set_memory_size(Some(total_bytes));
+        self.set_nvdimm_p_sizes(total_bytes - volatile_bytes, volatile_bytes);
+        Ok(())
+    }
+
+    // High Bandwidth Memory: `stacked_die_count` dies of `capacity_per_stack_gb` each
+    pub fn set_as_hbm3(&mut self, stacked_die_count: u8, capacity_per_stack_gb: u8) {
+        self.set_as_hbm(
+            MemoryType::Hbm3,
+            stacked_die_count,
+            capacity_per_stack_gb,
+            1024,
+        );
+    }
+
+    pub fn set_as_hbm2(&mut self, stacked_die_count: u8, capacity_gb: u8) {
+        self.set_as_hbm(MemoryType::Hbm2, stacked_die_count, capacity_gb, 256);
+    }
+
+    fn set_as_hbm(&mut self, memory_type: MemoryType, dies: u8, capacity_gb: u8, width: u16) {
+        self.data.memory_type = memory_type;
+        self.data.form_factor = FormFactor::Die;
+        self.set_technology(MemoryTechnology::Dram);
+        self.set_memory_size(Some(gb(u64::from(dies) * u64::from(capacity_gb))));
+        self.data.device_set = dies;
+        self.data.total_width = width.into();
+        self.data.data_width = width.into();
+    }
+
+    pub fn set_module_manufacturer(&mut self, id: JedecManufacturerId) {
+        self.data.module_manufacturer_id = u16::from(id).into();
+    }
+
+    // Product IDs are assigned by the manufacturer named in the matching manufacturer ID field,
+    // so they're stored as the two bytes given in SPD: the revision in the high byte and the
+    // component in the low byte, which comes first in the structure.
+    pub fn set_module_product_id_jedec(&mut self, high_byte: u8, low_byte: u8) {
+        self.data.module_product_id = u16::from_be_bytes([high_byte, low_byte]).into();
+    }
+
+    pub fn module_product_id_jedec(&self) -> (u8, u8) {
+        let [high_byte, low_byte] = self.data.module_product_id.get().to_be_bytes();
+        (high_byte, low_byte)
+    }
+
+    pub fn set_memory_subsystem_controller_manufacturer(&mut self, id: JedecManufacturerId) {
+        self.data.memory_subsystem_controller_manufacturer_id = u16::from(id).into();
+    }
+
+    pub fn set_memory_subsystem_controller_product_id_jedec(
+        &mut self,
+        high_byte: u8,
+        low_byte: u8,
+    ) {
+        self.data.memory_subsystem_controller_product_id =
+            u16::from_be_bytes([high_byte, low_byte]).into();
+    }
+
+    // Revisions are as read from SPD, with 0 meaning unknown
+    pub fn set_pmic0_manufacturer(&mut self, id: JedecManufacturerId) {
+        self.data.pmic0_manufacturer_id = u16::from(id).into();
+    }
+
+    pub fn set_pmic0_revision(&mut self, revision: u16) {
+        self.data.pmic0_revision_number = revision.into();
+    }
+
+    pub fn set_rcd_manufacturer(&mut self, id: JedecManufacturerId) {
+        self.data.rcd_manufacturer_id = u16::from(id).into();
+    }
+
+    pub fn set_rcd_revision(&mut self, revision: u16) {
+        self.data.rcd_revision_number = revision.into();
+    }
+
+    // Voltages are in millivolts, with 0 meaning unknown
+    pub fn set_minimum_voltage_mv(&mut self, mv: u16) {
+        self.data.minimum_voltage = mv.into();
+    }
+
+    pub fn set_maximum_voltage_mv(&mut self, mv: u16) {
+        self.data.maximum_voltage = mv.into();
+    }
+
+    pub fn set_configured_voltage_mv(&mut self, mv: u16) {
+        self.data.configured_voltage = mv.into();
+    }
+
+    // Sets all three voltages, or none of them if they're out of order
+    pub fn set_voltage_range(
+        &mut self,
+        min_mv: u16,
+        max_mv: u16,
+        configured_mv: u16,
+    ) -> Result<(), SmbiosError> {
+        if !voltage_range_is_valid(min_mv, max_mv, configured_mv) {
+            return Err(SmbiosError::VoltageRangeInvalid {
+                min: min_mv,
+                max: max_mv,
+                configured: configured_mv,
+            });
+        }
+        self.set_minimum_voltage_mv(min_mv);
+        self.set_maximum_voltage_mv(max_mv);
+        self.set_configured_voltage_mv(configured_mv);
+        Ok(())
+    }
+}
+
+// The ordering can't be checked if any of the voltages is unknown
+pub(crate) fn voltage_range_is_valid(min: u16, max: u16, configured: u16) -> bool {
+    min == 0 || max == 0 || configured == 0 || (min <= configured && configured <= max)
+}
+
+impl fmt::Display for MemoryDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Type 17 (Memory Device): locator=\"{}\", bank=\"{}\", type={:?}, ",
+            self.get_string(self.data.device_locator),
+            self.get_string(self.data.bank_locator),
+            self.data.memory_type
+        )?;
+        match self.get_memory_size() {
+            Some(size) => write!(f, "size={} MB, ", to_mb(size))?,
+            None => write!(f, "size=unknown, ")?,
+        }
+        write!(f, "speed={} MT/s", self.get_speed_mts())
+    }
+}
+
+// Type 19 SMBIOS table (Memory Array Mapped Address)
+simple_smbios_structure! {
+    19,
+    struct MemoryArrayMappedAddress {
+        data: struct Type19Data {
+            starting_address: U32,
+            ending_address: U32,
+            memory_array_handle: StructureHandle,
+            partition_width: u8,
+            extended_starting_address: U64,
+            extended_ending_address: U64,
+        }
+    }
+}
+static_assertions::const_assert!(size_of::<Type19Data>() == 0x1f);
+
+// The 32-bit address fields hold KiB, unless the starting address is all ones, in which case the
+// extended fields hold the range in bytes
+fn decode_address_range(
+    starting: u32,
+    ending: u32,
+    extended_starting: u64,
+    extended_ending: u64,
+) -> (u64, u64) {
+    match starting {
+        u32::MAX => (extended_starting, extended_ending),
+        starting => (u64::from(starting) << 10, (u64::from(ending) << 10) | 0x3ff),
+    }
+}
+
+impl MemoryArrayMappedAddress {
+    // The range in bytes, inclusive of the ending address
+    pub fn get_address_range(&self) -> (u64, u64) {
+        decode_address_range(
+            self.data.starting_address.get(),
+            self.data.ending_address.get(),
+            self.data.extended_starting_address.get(),
+            self.data.extended_ending_address.get(),
+        )
+    }
+
+    pub fn set_address_range(&mut self, starting: u64, ending: u64) {
+        self.data.starting_address = u32::MAX.into();
+        self.data.ending_address = u32::MAX.into();
+        self.data.extended_starting_address = starting.into();
+        self.data.extended_ending_address = ending.into();
+    }
+
+    pub fn try_set_address_range(&mut self, starting: u64, ending: u64) -> Result<(), SmbiosError> {
+        if starting > ending {
+            return Err(SmbiosError::InvalidAddressRange { starting, ending });
+        }
+        self.set_address_range(starting, ending);
+        Ok(())
+    }
+}
+
+// Type 20 SMBIOS table (Memory Device Mapped Address)
+simple_smbios_structure! {
+    20,
+    struct MemoryDeviceMappedAddress {
+        data: struct Type20Data {
+            starting_address: U32,
+            ending_address: U32,
+            memory_device_handle: StructureHandle,
+            memory_array_mapped_address_handle: StructureHandle,
+            partition_row_position: PartitionRowPosition,
+            interleave_position: InterleavePosition,
+            interleaved_data_depth: InterleaveDataDepth,
+            extended_starting_address: U64,
+            extended_ending_address: U64,
+        }
+    }
+}
+static_assertions::const_assert!(size_of::<Type20Data>() == 0x23);
+
+impl MemoryDeviceMappedAddress {
+    // The range in bytes, inclusive of the ending address
+    pub fn get_address_range(&self) -> (u64, u64) {
+        decode_address_range(
+            self.data.starting_address.get(),
+            self.data.ending_address.get(),
+            self.data.extended_starting_address.get(),
+            self.data.extended_ending_address.get(),
+        )
+    }
+
+    pub fn set_address_range(&mut self, starting: u64, ending: u64) {
+        self.data.starting_address = u32::MAX.into();
+        self.data.ending_address = u32::MAX.into();
+        self.data.extended_starting_address = starting.into();
+        self.data.extended_ending_address = ending.into();
+    }
+
+    pub fn try_set_address_range(&mut self, starting: u64, ending: u64) -> Result<(), SmbiosError> {
+        if starting > ending {
+            return Err(SmbiosError::InvalidAddressRange { starting, ending });
+        }
+        self.set_address_range(starting, ending);
+        Ok(())
+    }
+
+    pub fn set_interleaved_mapping(
+        &mut self,
+        position: InterleavePosition,
+        depth: InterleaveDataDepth,
+    ) {
+        self.data.interleave_position = position;
+        self.data.interleaved_data_depth = depth;
+    }
+}
+
+// Type 22 SMBIOS table (Portable Battery)
+// The SBDS fields are for batteries that support the Smart Battery Data Specification, and are
+// used instead of the manufacture date and serial number strings.
+simple_smbios_structure! {
+    22,
+    struct PortableBattery {
+        data: struct Type22Data {
+            location: StringIndex,
+            manufacturer: StringIndex,
+            manufacture_date: StringIndex,
+            serial_number: StringIndex,
+            device_name: StringIndex,
+            device_chemistry: BatteryChemistry,
+            design_capacity: U16,
+            design_voltage: U16,
+            sbds_version_number: StringIndex,
+            maximum_error_in_battery_data: u8,
+            sbds_serial_number: U16,
+            sbds_manufacture_date: U16,
+            sbds_device_chemistry: StringIndex,
+            design_capacity_multiplier: u8,
+            oem_specific: U32,
+        }
+    }
+}
+static_assertions::const_assert!(size_of::<Type22Data>() == 0x1a);
+
+impl PortableBattery {
+    // Capacities too large for the 16-bit field are scaled down by the smallest multiplier
+    // that makes them fit, losing any remainder. 0 means the capacity is unknown. Capacities
+    // which would need a multiplier above 255 are rejected, leaving the capacity unchanged.
+    pub fn set_design_capacity_mwh(&mut self, capacity_mwh: u32) -> Result<(), SmbiosError> {
+        let multiplier = capacity_mwh.div_ceil(0xffff).max(1);
+        let Ok(multiplier_byte) = u8::try_from(multiplier) else {
+            return Err(SmbiosError::BatteryCapacityTooLarge { capacity_mwh });
+        };
+        self.data.design_capacity = ((capacity_mwh / multiplier) as u16).into();
+        self.data.design_capacity_multiplier = multiplier_byte;
+        Ok(())
+    }
+
+    // A multiplier of 0 is treated as the default of 1
+    pub fn get_design_capacity_mwh(&self) -> u32 {
+        let multiplier = u32::from(self.data.design_capacity_multiplier.max(1));
+        u32::from(self.data.design_capacity.get()) * multiplier
+    }
+}
+
+// Type 26 SMBIOS table (Voltage Probe)
+// Readings are in millivolts, with the resolution in tenths of millivolts, the tolerance in
+// millivolts and the accuracy in hundredths of a percent.
+simple_smbios_structure! {
+    26,
+    struct VoltageProbe {
+        data: struct Type26Data {
+            description: StringIndex,
+            location_and_status: ProbeLocationAndStatus,
+            maximum_value: ProbeReading,
+            minimum_value: ProbeReading,
+            resolution: ProbeReading,
+            tolerance: ProbeReading,
+            accuracy: ProbeReading,
+            oem_defined: U32,
+            nominal_value: ProbeReading,
+        }
+    }
+}
+static_assertions::const_assert!(size_of::<Type26Data>() == 0x16);
+
+// Type 28 SMBIOS table (Temperature Probe)
+// Readings are in tenths of a degree C, with the resolution in thousandths of a degree C, the
+// tolerance in tenths of a degree C and the accuracy in hundredths of a percent.
+simple_smbios_structure! {
+    28,
+    struct TemperatureProbe {
+        data: struct Type28Data {
+            description: StringIndex,
+            location_and_status: ProbeLocationAndStatus,
+            maximum_value: ProbeReading,
+            minimum_value: ProbeReading,
+            resolution: ProbeReading,
+            tolerance: ProbeReading,
+            accuracy: ProbeReading,
+            oem_defined: U32,
+            nominal_value: ProbeReading,
+        }
+    }
+}
+static_assertions::const_assert!(size_of::<Type28Data>() == 0x16);
+
+// Type 29 SMBIOS table (Electrical Current Probe)
+// Readings are in milliamps, with the resolution in tenths of milliamps, the tolerance in
+// milliamps and the accuracy in hundredths of a percent.
+simple_smbios_structure! {
+    29,
+    struct CurrentProbe {
+        data: struct Type29Data {
+            description: StringIndex,
+            location_and_status: ProbeLocationAndStatus,
+            maximum_value: ProbeReading,
+            minimum_value: ProbeReading,
+            resolution: ProbeReading,
+            tolerance: ProbeReading,
+            accuracy: ProbeReading,
+            oem_defined: U32,
+            nominal_value: ProbeReading,
+        }
+    }
+}
+static_assertions::const_assert!(size_of::<Type29Data>() == 0x16);
+
+// Type 32 SMBIOS table (System Boot Information)
+#[derive(Clone, Debug, Default)]
+pub struct SystemBootInformation<'a> {
+    handle: u16,
+    status: BootStatus<'a>,
+}
+
+// The maximum structure length of 255 bytes, less the header, the reserved bytes and the
+// status code
+pub const MAX_BOOT_STATUS_PAYLOAD: usize = 255 - 4 - 6 - 1;
 
 impl<'a> SystemBootInformation<'a> {
-    pub fn new(handle: u16, status: BootStatus<'a>) -> Self {
-        Self { handle, status }
+    pub const TYPE_NUMBER: u8 = 32;
+
+    // The status is checked here, so that serializing it can't fail
+    pub fn new(handle: u16, status: BootStatus<'a>) -> Result<Self, SmbiosError> {
+        match status {
+            BootStatus::VendorSpecific(code, _) if !(128..=191).contains(&code) => {
+                return Err(SmbiosError::InvalidBootStatusCode { code });
+            }
+            BootStatus::ProductSpecific(code, _) if code < 192 => {
+                return Err(SmbiosError::InvalidBootStatusCode { code });
+            }
+            _ => (),
+        }
+        let s = Self { handle, status };
+        if s.payload_len() > MAX_BOOT_STATUS_PAYLOAD {
+            return Err(SmbiosError::StructureTooLarge);
+        }
+        Ok(s)
+    }
+
+    fn payload_len(&self) -> usize {
+        match &self.status {
+            BootStatus::PreviouslyRequestedImage(extra) => extra.len(),
+            BootStatus::VendorSpecific(_, extra) => extra.len(),
+            BootStatus::ProductSpecific(_, extra) => extra.len(),
+            _ => 0,
+        }
     }
 }
 
@@ -412,7 +2298,7 @@ impl SmbiosStructure for SystemBootInformation<'_> {
     fn serialize(&self, sink: &mut dyn Sink) {
         let mut output = Vec::new();
 
-        output.byte(32);
+        output.byte(Self::TYPE_NUMBER);
         output.byte(0); // length will be fixed up at the end
         output.word(self.handle);
 
@@ -433,149 +2319,2291 @@ impl SmbiosStructure for SystemBootInformation<'_> {
                 output.vec(extra);
             }
             BootStatus::SystemWatchdogTimer => output.byte(8),
-            BootStatus::VendorSpecific(code, extra) => {
-                assert!(*code >= 128 && *code <= 191);
-                output.byte(*code);
-                output.vec(extra);
-            }
-            BootStatus::ProductSpecific(code, extra) => {
-                assert!(*code >= 192);
+            BootStatus::VendorSpecific(code, extra) | BootStatus::ProductSpecific(code, extra) => {
                 output.byte(*code);
                 output.vec(extra);
             }
         }
 
-        // Fix up the length byte
-        output[1] = output.len().try_into().unwrap();
+        // Fix up the length byte. `new` limits the payload, so this always fits.
+        output[1] = output.len() as u8;
         output.byte(0);
         output.byte(0);
 
         sink.vec(&output);
     }
-}
 
-// Type 43 SMBIOS table (TPM Device)
-simple_smbios_structure! {
-    43,
-    struct TpmDevice {
-        data: struct Type43Data {
-            vendor_id: [u8; 4],
-            major_spec_version: u8,
-            minor_spec_version: u8,
-            firmware_version1: U32,
-            firmware_version2: U32,
-            description: StringIndex,
-            characteristics: U64,
-            oem_defined: U32,
-        }
+    fn byte_length(&self) -> usize {
+        // Header, reserved bytes, status code, payload and the string terminator
+        4 + 6 + 1 + self.payload_len() + 2
     }
 }
 
-// Type 44 SMBIOS table (Processor Additional Information)
-// See https://github.com/riscv/riscv-smbios/blob/main/riscv-smbios.adoc
-simple_smbios_structure! {
-    44,
-    struct RiscvProcessorAdditionalInformation {
-        data: struct RiscvType44Data {
-            referenced_handle: StructureHandle, // type 4
-            revision: U16,
-            structure_length: u8,
-            hart_id: U128,
-            boot_hart: u8,
-            mvendorid: U128,
-            marchid: U128,
-            mimplid: U128,
-            isa_supported: U32,
-            privilege_level_supported: u8,
-            m_exception_delegation: U128,
-            m_interrupt_delegation: U128,
-            xlen: Xlen,
-            mxlen: Xlen,
-            reserved: u8,
-            sxlen: Xlen,
-            uxlen: Xlen,
-        }
+impl SmbiosTable for SystemBootInformation<'static> {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
     }
-}
-static_assertions::const_assert!(size_of::<RiscvType44Data>() == 0x74);
 
-simple_smbios_structure! {
-    127,
-    struct EndOfTable {
-        data: struct Type127Data {
-        }
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use alloc::vec;
+// Type 32 with a status that owns its extra data, for keeping alongside other long-lived
+// structures. It serializes exactly as `SystemBootInformation` does.
+#[derive(Clone, Debug, Default)]
+pub struct OwnedSystemBootInformation {
+    handle: u16,
+    status: OwnedBootStatus,
+}
+
+impl OwnedSystemBootInformation {
+    pub const TYPE_NUMBER: u8 = SystemBootInformation::TYPE_NUMBER;
+
+    pub fn new(handle: u16, status: OwnedBootStatus) -> Result<Self, SmbiosError> {
+        SystemBootInformation::new(handle, status.as_boot_status())?;
+        Ok(Self { handle, status })
+    }
+
+    // The status was checked when this was created
+    fn borrowed(&self) -> SystemBootInformation<'_> {
+        SystemBootInformation {
+            handle: self.handle,
+            status: self.status.as_boot_status(),
+        }
+    }
+}
+
+impl From<SystemBootInformation<'_>> for OwnedSystemBootInformation {
+    fn from(s: SystemBootInformation) -> Self {
+        Self {
+            handle: s.handle,
+            status: s.status.into(),
+        }
+    }
+}
+
+impl SmbiosStructure for OwnedSystemBootInformation {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        self.borrowed().serialize(sink);
+    }
+
+    fn byte_length(&self) -> usize {
+        self.borrowed().byte_length()
+    }
+}
+
+impl SmbiosTable for OwnedSystemBootInformation {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
+    }
+
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct MemoryChannelDevice {
+    load: u8,
+    handle: MemoryDeviceHandle,
+}
+
+// Type 37 SMBIOS table (Memory Channel)
+// Each device is 3 bytes (load and handle), so the structure length depends on the number of
+// devices.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryChannel {
+    handle: u16,
+    channel_type: MemoryChannelType,
+    maximum_channel_load: u8,
+    devices: Vec<MemoryChannelDevice>,
+}
+
+impl MemoryChannel {
+    pub const TYPE_NUMBER: u8 = 37;
+
+    pub fn new(handle: u16) -> Self {
+        Self {
+            handle,
+            ..Default::default()
+        }
+    }
+
+    pub fn set_channel_type(&mut self, channel_type: MemoryChannelType) {
+        self.channel_type = channel_type;
+    }
+
+    pub fn set_maximum_channel_load(&mut self, load: u8) {
+        self.maximum_channel_load = load;
+    }
+
+    // The device isn't added if the length byte can't cover it
+    pub fn add_device(&mut self, load: u8, handle: MemoryDeviceHandle) -> Result<(), SmbiosError> {
+        if 7 + 3 * (self.devices.len() + 1) > 0xff {
+            return Err(SmbiosError::StructureTooLarge);
+        }
+        self.devices.push(MemoryChannelDevice { load, handle });
+        Ok(())
+    }
+}
+
+impl SmbiosStructure for MemoryChannel {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        sink.byte(Self::TYPE_NUMBER);
+        sink.byte((7 + 3 * self.devices.len()).try_into().unwrap());
+        sink.word(self.handle);
+        sink.byte(self.channel_type as u8);
+        sink.byte(self.maximum_channel_load);
+        sink.byte(self.devices.len().try_into().unwrap());
+        for device in &self.devices {
+            sink.byte(device.load);
+            sink.word(device.handle);
+        }
+        sink.byte(0);
+        sink.byte(0);
+    }
+
+    fn byte_length(&self) -> usize {
+        7 + 3 * self.devices.len() + 2
+    }
+}
+
+impl SmbiosTable for MemoryChannel {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
+    }
+
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
+        for device in &mut self.devices {
+            device.handle = remap(device.handle);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AdditionalInformationEntry {
+    referenced_handle: u16,
+    referenced_offset: u8,
+    string: String,
+    value: Vec<u8>,
+}
+
+impl AdditionalInformationEntry {
+    // Entry length, handle, offset and string index, followed by the value
+    fn entry_length(&self) -> usize {
+        5 + self.value.len()
+    }
+}
+
+// Type 40 SMBIOS table (Additional Information)
+// Each entry annotates a field (given by its offset) of another structure with a value and a
+// description string.
+#[derive(Clone, Debug, Default)]
+pub struct AdditionalInformation {
+    handle: u16,
+    entries: Vec<AdditionalInformationEntry>,
+}
+
+impl AdditionalInformation {
+    pub const TYPE_NUMBER: u8 = 40;
+
+    pub fn new(handle: u16) -> Self {
+        Self {
+            handle,
+            ..Default::default()
+        }
+    }
+
+    // The entry isn't added if the length byte can't cover it, which also keeps each entry's
+    // length and string index within a byte. An empty description is stored as no string.
+    pub fn add_entry(
+        &mut self,
+        handle: u16,
+        offset: u8,
+        value: &[u8],
+        description: &str,
+    ) -> Result<(), SmbiosError> {
+        if description.contains('\0') {
+            return Err(SmbiosError::InvalidString);
+        }
+        let entry = AdditionalInformationEntry {
+            referenced_handle: handle,
+            referenced_offset: offset,
+            string: description.into(),
+            value: value.into(),
+        };
+        if self.formatted_length() + entry.entry_length() > 0xff {
+            return Err(SmbiosError::StructureTooLarge);
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    fn formatted_length(&self) -> usize {
+        5 + self.entries.iter().map(|e| e.entry_length()).sum::<usize>()
+    }
+
+    fn strings(&self) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .map(|e| e.string.as_str())
+            .filter(|s| !s.is_empty())
+    }
+}
+
+impl SmbiosStructure for AdditionalInformation {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        // add_entry keeps all of these lengths and counts within a byte
+        sink.byte(Self::TYPE_NUMBER);
+        sink.byte(self.formatted_length() as u8);
+        sink.word(self.handle);
+        sink.byte(self.entries.len() as u8);
+        let mut index = 0;
+        for entry in &self.entries {
+            sink.byte(entry.entry_length() as u8);
+            sink.word(entry.referenced_handle);
+            sink.byte(entry.referenced_offset);
+            if entry.string.is_empty() {
+                sink.byte(0);
+            } else {
+                index += 1;
+                sink.byte(index);
+            }
+            sink.vec(&entry.value);
+        }
+        for s in self.strings() {
+            sink.vec(s.as_bytes());
+            sink.byte(0);
+        }
+        sink.byte(0);
+        if self.strings().next().is_none() {
+            sink.byte(0);
+        }
+    }
+
+    fn byte_length(&self) -> usize {
+        let strings: usize = self.strings().map(|s| s.len() + 1).sum();
+        self.formatted_length() + strings + if strings == 0 { 2 } else { 1 }
+    }
+}
+
+impl SmbiosTable for AdditionalInformation {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
+    }
+
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
+        for entry in &mut self.entries {
+            entry.referenced_handle = remap(entry.referenced_handle);
+        }
+    }
+}
+
+// Type 43 SMBIOS table (TPM Device)
+simple_smbios_structure! {
+    43,
+    struct TpmDevice {
+        data: struct Type43Data {
+            vendor_id: [u8; 4],
+            major_spec_version: u8,
+            minor_spec_version: u8,
+            firmware_version1: U32,
+            firmware_version2: U32,
+            description: StringIndex,
+            characteristics: U64,
+            oem_defined: U32,
+        }
+    }
+}
+
+impl TpmDevice {
+    pub fn set_tpm_characteristics(&mut self, flags: TpmDeviceCharacteristics) {
+        self.data.characteristics = flags.bits().into();
+    }
+}
+
+// Type 44 SMBIOS table (Processor Additional Information)
+// See https://github.com/riscv/riscv-smbios/blob/main/riscv-smbios.adoc
+simple_smbios_structure! {
+    44,
+    struct RiscvProcessorAdditionalInformation {
+        data: struct RiscvType44Data {
+            referenced_handle: StructureHandle, // type 4
+            revision_raw: U16, // set with set_revision
+            structure_length: u8,
+            hart_id: U128,
+            boot_hart: u8,
+            mvendorid: U128,
+            marchid: U128,
+            mimplid: U128,
+            isa_supported: U32,
+            privilege_level_supported: u8,
+            m_exception_delegation: U128,
+            m_interrupt_delegation: U128,
+            xlen: Xlen,
+            mxlen: Xlen,
+            reserved: u8,
+            sxlen: Xlen,
+            uxlen: Xlen,
+        }
+    }
+}
+static_assertions::const_assert!(size_of::<RiscvType44Data>() == 0x74);
+
+// Registered mvendorid values (JEDEC bank and ID, without parity)
+pub const MVENDORID_MICROCHIP: u128 = 0x029;
+pub const MVENDORID_ANDES: u128 = 0x31e;
+pub const MVENDORID_SIFIVE: u128 = 0x489;
+pub const MVENDORID_THEAD: u128 = 0x5b7;
+
+// Open-source microarchitecture marchid values, from the RISC-V marchid registry
+pub const MARCHID_ROCKET: u128 = 1;
+pub const MARCHID_BOOM: u128 = 2;
+pub const MARCHID_CVA6: u128 = 3;
+pub const MARCHID_CV32E40P: u128 = 4;
+pub const MARCHID_SPIKE: u128 = 5;
+
+// The structure length covers the RISC-V data, from the revision field to the end
+const RISCV_REV1_STRUCTURE_LENGTH: u8 = (size_of::<RiscvType44Data>() - 6) as u8;
+
+// The Type 44 revision and structure length fields for `rev`, given the architecture's structure
+// length for revision 1
+fn encode_type44_revision(rev: ProcessorSpecificRevision, rev1_length: u8) -> (U16, u8) {
+    let length = match rev {
+        ProcessorSpecificRevision::Rev1 => rev1_length,
+    };
+    ((rev as u16).into(), length)
+}
+
+// The revision, if it's known and the structure length is consistent with it
+fn decode_type44_revision(
+    revision: U16,
+    length: u8,
+    rev1_length: u8,
+) -> Option<ProcessorSpecificRevision> {
+    match revision.get() {
+        0x0100 if length == rev1_length => Some(ProcessorSpecificRevision::Rev1),
+        _ => None,
+    }
+}
+
+impl RiscvProcessorAdditionalInformation {
+    // Sets the revision along with the structure length that it implies
+    pub fn set_revision(&mut self, rev: RiscvRevision) {
+        (self.data.revision_raw, self.data.structure_length) =
+            encode_type44_revision(rev, RISCV_REV1_STRUCTURE_LENGTH);
+    }
+
+    // Returns the revision, if it's known and the structure length is consistent with it
+    pub fn get_revision(&self) -> Option<RiscvRevision> {
+        decode_type44_revision(
+            self.data.revision_raw,
+            self.data.structure_length,
+            RISCV_REV1_STRUCTURE_LENGTH,
+        )
+    }
+
+    // MXL is in the top two bits of misa, as read on an RV64 hart
+    pub fn set_xlen_from_misa(&mut self, misa: u64) {
+        let xlen = Xlen::from_misa_mxl((misa >> 62) as u8).unwrap_or(Xlen::Unsupported);
+        self.data.xlen = xlen;
+        self.data.mxlen = xlen;
+    }
+
+    pub fn set_vendor_and_arch(&mut self, vendor: u128, arch: u128, impl_id: u128) {
+        self.data.mvendorid = vendor.into();
+        self.data.marchid = arch.into();
+        self.data.mimplid = impl_id.into();
+    }
+
+    // Convenience setters for harts with 64-bit CSRs; the upper 64 bits are zeroed
+    pub fn set_mhartid_u64(&mut self, id: u64) {
+        self.set_mhartid_u128(id.into());
+    }
+
+    pub fn set_mhartid_u128(&mut self, id: u128) {
+        self.data.hart_id = id.into();
+    }
+
+    pub fn set_mvendorid_u64(&mut self, id: u64) {
+        self.data.mvendorid = u128::from(id).into();
+    }
+
+    pub fn set_marchid_u64(&mut self, id: u64) {
+        self.data.marchid = u128::from(id).into();
+    }
+
+    pub fn set_mimplid_u64(&mut self, id: u64) {
+        self.data.mimplid = u128::from(id).into();
+    }
+}
+
+// Type 44 SMBIOS table (Processor Additional Information) for LoongArch processors. This shares
+// its type number and revision numbering with the RISC-V structure, so the two can only be told
+// apart by the processor family of the referenced Type 4 structure.
+simple_smbios_structure! {
+    44,
+    struct LoongArchProcessorAdditionalInformation {
+        data: struct LoongArchType44Data {
+            referenced_handle: StructureHandle, // type 4
+            revision_raw: U16, // set with set_revision
+            structure_length: u8,
+            core_id: U64,
+            boot_core: u8,
+            vendor_id: U64,
+            cpu_id: U64,
+            isa_supported: LoongArchIsaFlags,
+            privilege_levels_supported: LoongArchPrivilegeLevels,
+        }
+    }
+}
+static_assertions::const_assert!(size_of::<LoongArchType44Data>() == 0x27);
+
+// The structure length covers the LoongArch data, from the revision field to the end
+const LOONGARCH_REV1_STRUCTURE_LENGTH: u8 = (size_of::<LoongArchType44Data>() - 6) as u8;
+
+impl LoongArchProcessorAdditionalInformation {
+    // Sets the revision along with the structure length that it implies
+    pub fn set_revision(&mut self, rev: LoongArchRevision) {
+        (self.data.revision_raw, self.data.structure_length) =
+            encode_type44_revision(rev, LOONGARCH_REV1_STRUCTURE_LENGTH);
+    }
+
+    // Returns the revision, if it's known and the structure length is consistent with it
+    pub fn get_revision(&self) -> Option<LoongArchRevision> {
+        decode_type44_revision(
+            self.data.revision_raw,
+            self.data.structure_length,
+            LOONGARCH_REV1_STRUCTURE_LENGTH,
+        )
+    }
+}
+
+// A firmware version, along with the format that its string representation follows
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FirmwareVersion {
+    // There's no format for three-part versions, so these are reported as free-form
+    SemVer { major: u16, minor: u16, patch: u16 },
+    MajorMinor { major: u16, minor: u16 },
+    Freeform(String),
+    HexEncoded(u32),
+}
+
+impl FirmwareVersion {
+    pub fn to_smbios_string(&self) -> String {
+        match self {
+            FirmwareVersion::SemVer {
+                major,
+                minor,
+                patch,
+            } => alloc::format!("{major}.{minor}.{patch}"),
+            FirmwareVersion::MajorMinor { major, minor } => alloc::format!("{major}.{minor}"),
+            FirmwareVersion::Freeform(s) => s.clone(),
+            FirmwareVersion::HexEncoded(v) => alloc::format!("0x{v:08X}"),
+        }
+    }
+
+    pub fn format_hint(&self) -> FirmwareVersionFormat {
+        match self {
+            FirmwareVersion::SemVer { .. } | FirmwareVersion::Freeform(_) => {
+                FirmwareVersionFormat::FreeForm
+            }
+            FirmwareVersion::MajorMinor { .. } => FirmwareVersionFormat::MajorMinor,
+            FirmwareVersion::HexEncoded(_) => FirmwareVersionFormat::Hex32,
+        }
+    }
+}
+
+// Type 45 SMBIOS table (Firmware Inventory Information)
+// The fixed part is 0x18 bytes, followed by the handles of the associated components.
+#[derive(Clone, Debug)]
+pub struct FirmwareInventoryInformation {
+    handle: u16,
+    component_name: StringIndex,
+    firmware_version: StringIndex,
+    version_format: FirmwareVersionFormat,
+    firmware_id: StringIndex,
+    firmware_id_format: FirmwareIdFormat,
+    release_date: StringIndex,
+    manufacturer: StringIndex,
+    lowest_supported_version: StringIndex,
+    image_size: u64,
+    characteristics: FirmwareInventoryCharacteristics,
+    state: FirmwareInventoryState,
+    associated_components: Vec<u16>,
+    strings: Vec<String>,
+}
+
+impl FirmwareInventoryInformation {
+    pub const TYPE_NUMBER: u8 = 45;
+
+    pub fn new(handle: u16) -> Self {
+        Self {
+            handle,
+            component_name: 0,
+            firmware_version: 0,
+            version_format: FirmwareVersionFormat::default(),
+            firmware_id: 0,
+            firmware_id_format: FirmwareIdFormat::default(),
+            release_date: 0,
+            manufacturer: 0,
+            lowest_supported_version: 0,
+            // All ones means the size is unknown
+            image_size: u64::MAX,
+            characteristics: FirmwareInventoryCharacteristics::empty(),
+            state: FirmwareInventoryState::default(),
+            associated_components: Vec::new(),
+            strings: Vec::new(),
+        }
+    }
+
+    // Replaces the string at `index` if there is one, otherwise adds a new one
+    fn set_string(&mut self, index: StringIndex, s: &str) -> StringIndex {
+        match index {
+            0 => {
+                self.strings.push(s.into());
+                self.strings.len().try_into().unwrap()
+            }
+            i => {
+                self.strings[usize::from(i) - 1] = s.into();
+                i
+            }
+        }
+    }
+
+    pub fn set_component_name(&mut self, s: &str) {
+        self.component_name = self.set_string(self.component_name, s);
+    }
+
+    // Sets the version string and its format together, so they can't disagree
+    pub fn set_firmware_version(&mut self, version: FirmwareVersion) {
+        self.firmware_version = self.set_string(self.firmware_version, &version.to_smbios_string());
+        self.version_format = version.format_hint();
+    }
+
+    pub fn set_firmware_id(&mut self, s: &str, format: FirmwareIdFormat) {
+        self.firmware_id = self.set_string(self.firmware_id, s);
+        self.firmware_id_format = format;
+    }
+
+    pub fn set_release_date(&mut self, s: &str) {
+        self.release_date = self.set_string(self.release_date, s);
+    }
+
+    pub fn set_manufacturer(&mut self, s: &str) {
+        self.manufacturer = self.set_string(self.manufacturer, s);
+    }
+
+    pub fn set_lowest_supported_version(&mut self, s: &str) {
+        self.lowest_supported_version = self.set_string(self.lowest_supported_version, s);
+    }
+
+    pub fn set_image_size(&mut self, bytes: Option<u64>) {
+        self.image_size = bytes.unwrap_or(u64::MAX);
+    }
+
+    pub fn set_characteristics(&mut self, characteristics: FirmwareInventoryCharacteristics) {
+        self.characteristics = characteristics;
+    }
+
+    pub fn set_state(&mut self, state: FirmwareInventoryState) {
+        self.state = state;
+    }
+
+    // The handle isn't added if the length byte can't cover it
+    pub fn add_associated_component(&mut self, handle: u16) -> Result<(), SmbiosError> {
+        if 0x18 + 2 * (self.associated_components.len() + 1) > 0xff {
+            return Err(SmbiosError::StructureTooLarge);
+        }
+        self.associated_components.push(handle);
+        Ok(())
+    }
+}
+
+impl SmbiosStructure for FirmwareInventoryInformation {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        sink.byte(Self::TYPE_NUMBER);
+        sink.byte(
+            (0x18 + 2 * self.associated_components.len())
+                .try_into()
+                .unwrap(),
+        );
+        sink.word(self.handle);
+        sink.byte(self.component_name);
+        sink.byte(self.firmware_version);
+        sink.byte(self.version_format as u8);
+        sink.byte(self.firmware_id);
+        sink.byte(self.firmware_id_format as u8);
+        sink.byte(self.release_date);
+        sink.byte(self.manufacturer);
+        sink.byte(self.lowest_supported_version);
+        sink.qword(self.image_size);
+        sink.word(self.characteristics.bits());
+        sink.byte(self.state as u8);
+        sink.byte(self.associated_components.len().try_into().unwrap());
+        for handle in &self.associated_components {
+            sink.word(*handle);
+        }
+        for s in &self.strings {
+            sink.vec(s.as_bytes());
+            sink.byte(0);
+        }
+        sink.byte(0);
+        if self.strings.is_empty() {
+            sink.byte(0);
+        }
+    }
+
+    fn byte_length(&self) -> usize {
+        let strings: usize = self.strings.iter().map(|s| s.len() + 1).sum();
+        0x18 + 2 * self.associated_components.len()
+            + strings
+            + if self.strings.is_empty() { 2 } else { 1 }
+    }
+}
+
+impl SmbiosTable for FirmwareInventoryInformation {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
+    }
+
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
+        for handle in &mut self.associated_components {
+            *handle = remap(*handle);
+        }
+    }
+}
+
+// Type 127 SMBIOS table (End-of-Table)
+// The table can be padded with zeros after this structure, so that its total length is a
+// multiple of the alignment. The padding depends on where the structure falls in the table, so
+// on its own it's serialized as if it were at the start.
+#[derive(Clone, Debug)]
+pub struct EndOfTable {
+    handle: u16,
+    alignment: usize,
+}
+
+impl EndOfTable {
+    pub const TYPE_NUMBER: u8 = 127;
+
+    pub fn new(handle: u16) -> Self {
+        Self {
+            handle,
+            alignment: 1,
+        }
+    }
+
+    // Pads the table to a 4-byte boundary
+    pub fn with_padding(handle: u16) -> Self {
+        let mut e = Self::new(handle);
+        e.set_alignment_bytes(4);
+        e
+    }
+
+    // As with `Sink::align_to`, alignments of 0 and 1 don't pad
+    pub fn set_alignment_bytes(&mut self, n: usize) {
+        self.alignment = n.max(1);
+    }
+
+    pub fn get_handle(&self) -> u16 {
+        self.handle
+    }
+}
+
+impl SmbiosStructure for EndOfTable {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        self.serialize_at(sink, 0);
+    }
+
+    fn byte_length(&self) -> usize {
+        self.byte_length_at(0)
+    }
+
+    fn serialize_at(&self, sink: &mut dyn Sink, offset: usize) {
+        sink.byte(Self::TYPE_NUMBER);
+        sink.byte(4);
+        sink.word(self.handle);
+        sink.byte(0);
+        sink.byte(0);
+        for _ in 6..self.byte_length_at(offset) {
+            sink.byte(0);
+        }
+    }
+
+    fn byte_length_at(&self, offset: usize) -> usize {
+        (offset + 6).next_multiple_of(self.alignment) - offset
+    }
+}
+
+impl SmbiosTable for EndOfTable {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
+    }
+
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{format, vec};
+
+    #[test]
+    fn test_oem_strings_checked() {
+        let mut o = OemStrings::new(11);
+        assert_eq!(
+            o.add_string_checked("bad\0string"),
+            Err(SmbiosError::InvalidString)
+        );
+        assert_eq!(
+            o.add_string_checked("caf\u{e9}"),
+            Err(SmbiosError::InvalidString)
+        );
+        assert_eq!(o.add_string_checked(""), Err(SmbiosError::InvalidString));
+
+        // The strings can be longer than the length byte could cover, but there can't be more
+        // than 255 of them
+        for _ in 0..OemStrings::MAX_STRINGS {
+            o.add_string("0123456789");
+        }
+        assert_eq!(
+            o.add_string_checked("0123456789"),
+            Err(SmbiosError::TooManyStrings { limit: 255 })
+        );
+        assert_eq!(o.total_string_bytes(), 255 * 11);
+        let mut output = vec![];
+        o.serialize(&mut output);
+        assert_eq!((output[1], output[4]), (5, 255));
+        assert_eq!(o.byte_length(), output.len());
+
+        let mut output = vec![];
+        let o = OemStrings::new(11);
+        o.serialize(&mut output);
+        assert_eq!(output, [11, 5, 11, 0, 0, 0, 0]);
+        assert_eq!(o.byte_length(), output.len());
+    }
+
+    #[test]
+    fn test_type0_release_date() {
+        let mut b = BiosInformation::new(0);
+        b.set_release_date_from_parts(1, 5, 2024).unwrap();
+        assert_eq!(b.get_string(b.data.bios_release_date), "01/05/2024");
+
+        b.set_release_date_iso("2023-11-30").unwrap();
+        assert_eq!(b.get_string(b.data.bios_release_date), "11/30/2023");
+
+        assert_eq!(
+            b.set_release_date_from_parts(13, 1, 2024),
+            Err(SmbiosError::InvalidDate)
+        );
+        assert_eq!(
+            b.set_release_date_from_parts(1, 0, 2024),
+            Err(SmbiosError::InvalidDate)
+        );
+        assert_eq!(
+            b.set_release_date_from_parts(1, 1, 1899),
+            Err(SmbiosError::InvalidDate)
+        );
+        for bad in [
+            "2023-1-30",
+            "2023/11/30",
+            "2023-11-30-01",
+            "2023-11",
+            "20x3-11-30",
+        ] {
+            assert_eq!(b.set_release_date_iso(bad), Err(SmbiosError::InvalidDate));
+        }
+        // Rejected dates leave the existing one alone
+        assert_eq!(b.get_string(b.data.bios_release_date), "11/30/2023");
+    }
+
+    #[test]
+    fn test_type0_runtime_size() {
+        let mut output = vec![];
+        let mut b = BiosInformation::new(0);
+        b.set_runtime_size_kb(256);
+        b.serialize(&mut output);
+        assert_eq!(&output[6..8], &[0x00, 0xc0]);
+        assert_eq!(b.get_runtime_size_kb(), 256);
+
+        b.set_bios_starting_address_segment(0xe800.into());
+        assert_eq!(b.get_runtime_size_kb(), 96);
+
+        for kb in [0, 1024, 2048] {
+            let mut output = vec![];
+            b.set_runtime_size_kb(kb);
+            b.serialize(&mut output);
+            assert_eq!(&output[6..8], &[0, 0]);
+            assert_eq!(b.get_runtime_size_kb(), 1024);
+        }
+    }
+
+    #[test]
+    fn test_type3() {
+        let expected = [
+            3u8, 0x1c, 3, 0, 1, 0x9d, 0, 0, 0, 3, 3, 3, 3, 0, 0, 0, 0, 10, 2, 2, 3, 0x03, 4, 8,
+            0x82, 1, 1, 0, b'A', b'C', b'M', b'E', 0, 0,
+        ];
+
+        // A 10U blade enclosure holding 4-8 server blades and a single chassis (Type 2 board)
+        let mut output = vec![];
+        let mut e = SystemEnclosure::new(3);
+        e.set_manufacturer("ACME");
+        e.set_chassis_type(ChassisType::BladeEnclosure);
+        e.set_chassis_lock(true);
+        e.set_boot_up_state(ChassisState::Safe);
+        e.set_power_supply_state(ChassisState::Safe);
+        e.set_thermal_state(ChassisState::Safe);
+        e.set_security_status(ChassisSecurityStatus::None);
+        e.set_height(10);
+        e.set_number_of_power_cords(2);
+        let server_blade = ContainedElementType::baseboard_type(0x03).unwrap();
+        e.add_contained_element(server_blade, 4, 8).unwrap();
+        let baseboard = ContainedElementType::smbios_type(2).unwrap();
+        e.add_contained_element(baseboard, 1, 1).unwrap();
+        e.serialize(&mut output);
+        assert_eq!(expected.as_slice(), output);
+        assert_eq!(e.byte_length(), output.len());
+
+        assert_eq!(
+            ContainedElementType::smbios_type(0x7f),
+            Err(RangeError(0x7f))
+        );
+        assert_eq!(
+            e.add_contained_element(server_blade, 0xff, 0xff),
+            Err(SmbiosError::InvalidContainedElementRange {
+                minimum: 0xff,
+                maximum: 0xff
+            })
+        );
+        assert_eq!(
+            e.add_contained_element(server_blade, 4, 2),
+            Err(SmbiosError::InvalidContainedElementRange {
+                minimum: 4,
+                maximum: 2
+            })
+        );
+        assert_eq!(
+            e.add_contained_element(server_blade, 0, 0),
+            Err(SmbiosError::InvalidContainedElementRange {
+                minimum: 0,
+                maximum: 0
+            })
+        );
+        assert_eq!(
+            ContainedElementType::baseboard_type(0x83),
+            Err(RangeError(0x83))
+        );
+    }
+
+    #[test]
+    fn test_type3_element_limit() {
+        let mut e = SystemEnclosure::new(3);
+        let baseboard = ContainedElementType::smbios_type(2).unwrap();
+        for _ in 0..77 {
+            e.add_contained_element(baseboard, 1, 1).unwrap();
+        }
+        assert_eq!(
+            e.add_contained_element(baseboard, 1, 1),
+            Err(SmbiosError::StructureTooLarge)
+        );
+        let mut output = vec![];
+        e.serialize(&mut output);
+        assert_eq!(output[1], 0x16 + 3 * 77);
+        assert_eq!(output[0x13], 77);
+        assert_eq!(e.byte_length(), output.len());
+    }
+
+    #[test]
+    fn test_type3_strings() {
+        let mut e = SystemEnclosure::new(3);
+        e.set_manufacturer("First");
+        e.set_version("Version");
+        e.set_manufacturer("Second");
+        e.set_serial_number_or_empty("");
+        let mut output = vec![];
+        e.serialize(&mut output);
+        assert_eq!((output[4], output[6], output[7]), (1, 2, 0));
+        assert!(output.ends_with(b"Second\0Version\0\0"));
+
+        // Clearing a string renumbers the ones after it
+        e.clear_manufacturer();
+        let mut output = vec![];
+        e.serialize(&mut output);
+        assert_eq!((output[4], output[6]), (0, 1));
+        assert!(output.ends_with(b"\0Version\0\0"));
+        assert_eq!(e.byte_length(), output.len());
+    }
+
+    #[test]
+    fn test_type4_upgrade() {
+        assert_eq!(ProcessorUpgrade::SocketA as u8, 0x0e);
+        assert_eq!(ProcessorUpgrade::SocketLga2011_3 as u8, 0x2b);
+        assert_eq!(ProcessorUpgrade::SocketSp5 as u8, 0x4a);
+        assert_eq!(ProcessorUpgrade::SocketLga7529 as u8, 0x50);
+
+        let mut output = vec![];
+        let mut p = ProcessorInformation::new_risc_v(4, Xlen::Xlen64).unwrap();
+        p.serialize(&mut output);
+        assert_eq!(output[0x19], ProcessorUpgrade::None as u8);
+
+        let mut output = vec![];
+        p.set_upgrade(ProcessorUpgrade::SocketLga4677);
+        p.serialize(&mut output);
+        assert_eq!(output[0x19], 0x3f);
+    }
+
+    #[test]
+    fn test_type4_new_risc_v() {
+        for (xlen, family) in [
+            (Xlen::Xlen32, 0x200),
+            (Xlen::Xlen64, 0x201),
+            (Xlen::Xlen128, 0x202),
+        ] {
+            let p = ProcessorInformation::new_risc_v(4, xlen).unwrap();
+            assert_eq!(p.get_effective_processor_family(), family);
+        }
+        assert!(ProcessorInformation::new_risc_v(4, Xlen::Unsupported).is_none());
+    }
+
+    #[test]
+    fn test_type4_characteristics() {
+        let mut output = vec![];
+        let mut p = ProcessorInformation::new_risc_v(4, Xlen::Xlen64).unwrap();
+        p.set_processor_characteristics(
+            RiscvProcessorCharacteristics1::Bits64Capable
+                | RiscvProcessorCharacteristics1::MultiCore
+                | RiscvProcessorCharacteristics1::HardwareThread,
+        );
+        p.serialize(&mut output);
+        assert_eq!(&output[0x26..0x28], &[0x1c, 0x00]);
+    }
+
+    #[test]
+    fn test_type4_cache_handles() {
+        let mut output = vec![];
+        let mut p = ProcessorInformation::new_risc_v(4, Xlen::Xlen64).unwrap();
+        p.set_cache_handles(Some(0x70), Some(0x71), None);
+        p.serialize(&mut output);
+        assert_eq!(&output[0x1a..0x20], &[0x70, 0, 0x71, 0, 0xff, 0xff]);
+
+        let mut output = vec![];
+        p.set_no_caches();
+        p.serialize(&mut output);
+        assert_eq!(&output[0x1a..0x20], &[0xff; 6]);
+    }
+
+    #[test]
+    fn test_type4_socket_status() {
+        // A two socket server with only the first socket populated
+        let mut p0 = ProcessorInformation::new(4);
+        p0.set_socket_designation("CPU0");
+        p0.set_socket_status(true, CpuStatus::Enabled);
+        let mut p1 = ProcessorInformation::new(5);
+        p1.set_socket_designation("CPU1");
+        p1.set_socket_status(false, CpuStatus::Enabled);
+
+        assert!(p0.is_populated() && p0.is_enabled());
+        assert!(!p1.is_populated() && !p1.is_enabled());
+
+        let mut output = vec![];
+        p0.serialize(&mut output);
+        p1.serialize(&mut output);
+        assert_eq!(output[0x18], 0x41);
+        assert_eq!(output[0x32 + 6 + 0x18], 0);
+
+        p0.set_socket_status(true, CpuStatus::DisabledByBios);
+        assert!(p0.is_populated() && !p0.is_enabled());
+    }
+
+    #[test]
+    fn test_type4_x86_cpuid() {
+        // Haswell-E
+        let mut p = ProcessorInformation::new(4);
+        p.set_processor_id_from_x86_cpuid(0x0003_06f2, 0xbfeb_fbff);
+        assert_eq!(p.get_x86_cpuid_signature(), 0x0003_06f2);
+
+        let mut output = vec![];
+        p.serialize(&mut output);
+        assert_eq!(
+            &output[0x08..0x10],
+            &[0xf2, 0x06, 0x03, 0x00, 0xff, 0xfb, 0xeb, 0xbf]
+        );
+    }
+
+    #[test]
+    fn test_type127_alignment() {
+        let mut e = EndOfTable::with_padding(127);
+        assert_eq!((e.byte_length(), e.byte_length_at(2)), (8, 6));
+        for n in [0, 1] {
+            e.set_alignment_bytes(n);
+            assert_eq!((e.byte_length(), e.byte_length_at(3)), (6, 6));
+        }
+    }
+
+    #[test]
+    fn test_type4_riscv_mvendorid() {
+        // SiFive
+        let mut p = ProcessorInformation::new_risc_v(4, Xlen::Xlen64).unwrap();
+        p.set_processor_id_riscv_mvendorid(0x489);
+
+        let mut output = vec![];
+        p.serialize(&mut output);
+        assert_eq!(output[0x05], ProcessorType::CentralProcessor as u8);
+        assert_eq!(output[0x06], ProcessorFamily::ObtainFrom2 as u8);
+        assert_eq!(&output[0x08..0x10], &[0x89, 0x04, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            p.get_effective_processor_family(),
+            ProcessorFamily2::RiscvRv64 as u16
+        );
+
+        // Without an XLEN, the family isn't pointed at an unset Processor Family 2
+        let mut p = ProcessorInformation::new(4);
+        p.set_processor_id_riscv_mvendorid(0x489);
+        assert_ne!(
+            p.get_effective_processor_family(),
+            ProcessorFamily::ObtainFrom2 as u16
+        );
+    }
+
+    #[test]
+    fn test_type14() {
+        let expected = [
+            14u8, 0x0b, 0x0e, 0, 1, 4, 0x10, 0, 4, 0x11, 0, b'C', b'P', b'U', b's', 0, 0,
+        ];
+
+        let mut output = vec![];
+        let mut g = GroupAssociations::new(0xe);
+        g.set_group_name("CPUs");
+        g.add_item(GroupAssociationItem::new(4, 0x10)).unwrap();
+        g.add_item(GroupAssociationItem::new(4, 0x11)).unwrap();
+        g.serialize(&mut output);
+        assert_eq!(expected.as_slice(), output);
+        assert_eq!(g.byte_length(), output.len());
+
+        let mut output = vec![];
+        GroupAssociations::new(0xe).serialize(&mut output);
+        assert_eq!(output, [14, 5, 0x0e, 0, 0, 0, 0]);
+
+        // The length byte covers at most 83 items after the 5-byte fixed section
+        let mut g = GroupAssociations::new(0xe);
+        for i in 0..83 {
+            g.add_item(GroupAssociationItem::new(4, i)).unwrap();
+        }
+        assert_eq!(
+            g.add_item(GroupAssociationItem::new(4, 83)),
+            Err(SmbiosError::StructureTooLarge)
+        );
+        let mut output = vec![];
+        g.serialize(&mut output);
+        assert_eq!(output[1], 5 + 3 * 83);
+        assert_eq!(g.byte_length(), output.len());
+    }
+
+    #[test]
+    fn test_type15() {
+        let expected = [
+            15u8, 0x1f, 0x0f, 0, 0x00, 0x10, 0, 0, 0x10, 0, 3, 1, 0x78, 0x56, 0x34, 0x12, 0, 0,
+            0xf0, 0xfe, 1, 4, 2, 0x01, 0x00, 0x02, 0x00, 0x08, 0x01, 0x16, 0x00, 0, 0,
+        ];
+
+        let mut output = vec![];
+        let mut l = SystemEventLog::new_memory_mapped(0xf, 0xfef0_0000);
+        l.set_log_area(0x1000, 0, 0x10);
+        l.set_log_status(LogStatus::Valid);
+        l.set_log_change_token(0x1234_5678);
+        l.set_log_header_format(LogHeaderFormat::Type1);
+        // Single-bit ECC, multi-bit ECC, PCI parity error and log area reset
+        l.add_event_type_descriptor(0x01, 0x00).unwrap();
+        l.add_event_type_descriptor(0x02, 0x00).unwrap();
+        l.add_event_type_descriptor(0x08, 0x01).unwrap();
+        l.add_event_type_descriptor(0x16, 0x00).unwrap();
+        l.serialize(&mut output);
+        assert_eq!(expected.as_slice(), output);
+        assert_eq!(l.byte_length(), output.len());
+
+        let mut output = vec![];
+        let l = SystemEventLog::new_indexed_io(0xf, LogAccessMethod::IndexedIo1x8Bit, 0x70, 0x71)
+            .unwrap();
+        l.serialize(&mut output);
+        assert_eq!(output[0xa], 0);
+        assert_eq!(&output[0x10..0x14], &[0x70, 0, 0x71, 0]);
+
+        assert_eq!(
+            SystemEventLog::new_indexed_io(0xf, LogAccessMethod::MemoryMapped32Bit, 0x70, 0x71)
+                .unwrap_err(),
+            SmbiosError::InvalidLogAccessMethod {
+                access_method: LogAccessMethod::MemoryMapped32Bit as u8
+            }
+        );
+    }
+
+    #[test]
+    fn test_type15_descriptor_limit() {
+        let mut l = SystemEventLog::new_gpnv(0xf, 0x30);
+        for event_type in 0..116 {
+            l.add_event_type_descriptor(event_type, 0).unwrap();
+        }
+        assert_eq!(
+            l.add_event_type_descriptor(0x16, 0),
+            Err(SmbiosError::StructureTooLarge)
+        );
+        let mut output = vec![];
+        l.serialize(&mut output);
+        assert_eq!((output[1], output[0x15]), (0x17 + 2 * 116, 116));
+        assert_eq!(l.byte_length(), output.len());
+    }
+
+    #[test]
+    fn test_type22() {
+        let expected = [
+            22u8, 0x1a, 22, 0, 1, 2, 0, 0, 3, 6, 0x50, 0xc3, 0x2c, 0x2e, 0, 0xff, 0x34, 0x12, 0x4f,
+            0x5a, 0, 2, 0, 0, 0, 0, b'F', b'r', b'o', b'n', b't', 0, b'A', b'C', b'M', b'E', 0,
+            b'B', b'A', b'T', b'0', 0, 0,
+        ];
+
+        // A 100 Wh laptop battery, which needs a multiplier of 2
+        let mut output = vec![];
+        let mut b = PortableBattery::new(22);
+        b.set_location("Front");
+        b.set_manufacturer("ACME");
+        b.set_device_name("BAT0");
+        b.set_device_chemistry(BatteryChemistry::LithiumIon);
+        b.set_design_capacity_mwh(100_000).unwrap();
+        b.set_design_voltage(11_820.into());
+        b.set_maximum_error_in_battery_data(0xff);
+        b.set_sbds_serial_number(0x1234.into());
+        // 2025-02-15, as ((year - 1980) << 9) | (month << 5) | day
+        b.set_sbds_manufacture_date(((45 << 9) | (2 << 5) | 15).into());
+        b.serialize(&mut output);
+        assert_eq!(expected.as_slice(), output);
+        assert_eq!(b.get_design_capacity_mwh(), 100_000);
+
+        b.set_design_capacity_mwh(48_000).unwrap();
+        assert_eq!(b.get_design_capacity_mwh(), 48_000);
+        // 200001 / 4 is rounded down
+        b.set_design_capacity_mwh(200_001).unwrap();
+        assert_eq!(b.get_design_capacity_mwh(), 200_000);
+        b.set_design_capacity_mwh(0).unwrap();
+        assert_eq!(b.get_design_capacity_mwh(), 0);
+
+        // 0xffff * 255 is the largest capacity which can be stored
+        b.set_design_capacity_mwh(0xffff * 255).unwrap();
+        assert_eq!(b.get_design_capacity_mwh(), 0xffff * 255);
+        assert_eq!(
+            b.set_design_capacity_mwh(0xffff * 255 + 1),
+            Err(SmbiosError::BatteryCapacityTooLarge {
+                capacity_mwh: 0xffff * 255 + 1
+            })
+        );
+        assert_eq!(b.get_design_capacity_mwh(), 0xffff * 255);
+    }
+
+    #[test]
+    fn test_probes() {
+        let mut v = VoltageProbe::new(26);
+        v.set_description("VDD_CORE");
+        v.set_location_and_status(ProbeLocationAndStatus::new(
+            ProbeLocation::Processor,
+            ProbeStatus::Ok,
+        ));
+        v.set_nominal_value(ProbeReading::from_value(900));
+        let mut t = TemperatureProbe::new(28);
+        t.set_location_and_status(ProbeLocationAndStatus::new(
+            TemperatureProbeLocation::DriveBackPlane,
+            ProbeStatus::Critical,
+        ));
+        t.set_minimum_value(ProbeReading::from_value(-400));
+
+        let mut voltage = vec![];
+        v.serialize(&mut voltage);
+        let mut temperature = vec![];
+        t.serialize(&mut temperature);
+        let mut current = vec![];
+        CurrentProbe::new(29).serialize(&mut current);
+
+        assert_eq!(voltage[5], 0x63);
+        assert_eq!(&voltage[0x14..0x16], &[0x84, 0x03]);
+        assert_eq!(temperature[5], 0xaf);
+        assert_eq!(&temperature[8..10], &[0x70, 0xfe]);
+        assert_eq!(ProbeReading::from_value(-400).value(), Some(-400));
+
+        // The unknown sentinel is the same for every probe type
+        for probe in [&voltage, &temperature, &current] {
+            assert_eq!(&probe[6..8], &[0x00, 0x80]);
+            assert_eq!(&probe[0xa..0xc], &[0x00, 0x80]);
+        }
+        assert_eq!(current[5], 0x42);
+        assert_eq!(ProbeReading::UNKNOWN.value(), None);
+    }
+
+    #[test]
+    fn test_type40() {
+        let expected = [
+            40u8, 0x12, 0x28, 0, 2, // header and count
+            6, 4, 0, 0x14, 1, 0x42, // annotate processor max speed
+            7, 4, 0, 0x08, 2, 0xaa, 0xbb, // annotate processor id
+            b'O', b'C', 0, b'I', b'D', 0, 0,
+        ];
+
+        let mut output = vec![];
+        let mut a = AdditionalInformation::new(0x28);
+        a.add_entry(4, 0x14, &[0x42], "OC").unwrap();
+        a.add_entry(4, 0x08, &[0xaa, 0xbb], "ID").unwrap();
+        a.serialize(&mut output);
+        assert_eq!(expected.as_slice(), output);
+        assert_eq!(a.byte_length(), output.len());
+    }
+
+    #[test]
+    fn test_type40_limits() {
+        let mut a = AdditionalInformation::new(0x28);
+        assert_eq!(
+            a.add_entry(4, 0x14, &[0; 246], "Too long"),
+            Err(SmbiosError::StructureTooLarge)
+        );
+        assert_eq!(
+            a.add_entry(4, 0x14, &[0x42], "Bad\0"),
+            Err(SmbiosError::InvalidString)
+        );
+        a.add_entry(4, 0x14, &[0; 245], "Fits").unwrap();
+        assert_eq!(
+            a.add_entry(4, 0x14, &[], ""),
+            Err(SmbiosError::StructureTooLarge)
+        );
+
+        // An empty description has no string, and the following strings are numbered from 1
+        let mut a = AdditionalInformation::new(0x28);
+        a.add_entry(4, 0x14, &[0x42], "").unwrap();
+        a.add_entry(4, 0x08, &[0xaa], "ID").unwrap();
+        let mut output = vec![];
+        a.serialize(&mut output);
+        assert_eq!((output[9], output[15]), (0, 1));
+        assert!(output.ends_with(b"\xaaID\0\0"));
+        assert_eq!(a.byte_length(), output.len());
+
+        let mut a = AdditionalInformation::new(0x28);
+        a.add_entry(4, 0x14, &[0x42], "").unwrap();
+        let mut output = vec![];
+        a.serialize(&mut output);
+        assert_eq!(&output[output.len() - 3..], &[0x42, 0, 0]);
+        assert_eq!(a.byte_length(), output.len());
+    }
+
+    #[test]
+    fn test_get_address_range() {
+        let mut a = MemoryArrayMappedAddress::new(0x19);
+        a.set_address_range(0x1_0000_0000, 0x1_ffff_ffff);
+        assert_eq!(a.get_address_range(), (0x1_0000_0000, 0x1_ffff_ffff));
+        // The 32-bit fields hold KiB, and the ending address covers the whole of its last KiB
+        a.set_starting_address(0x40.into());
+        a.set_ending_address(0x7f.into());
+        assert_eq!(a.get_address_range(), (0x1_0000, 0x1_ffff));
+
+        let mut d = MemoryDeviceMappedAddress::new(0x20);
+        d.set_address_range(0x8000_0000, 0x4_7fff_ffff);
+        assert_eq!(d.get_address_range(), (0x8000_0000, 0x4_7fff_ffff));
+        d.set_starting_address(0.into());
+        d.set_ending_address(0x3ff.into());
+        assert_eq!(d.get_address_range(), (0, 0xf_ffff));
+    }
+
+    #[test]
+    fn test_try_set_address_range() {
+        let mut a = MemoryArrayMappedAddress::new(0x19);
+        assert_eq!(a.try_set_address_range(0x1000, 0x1fff), Ok(()));
+        assert_eq!(
+            a.try_set_address_range(0x2000, 0x1fff),
+            Err(SmbiosError::InvalidAddressRange {
+                starting: 0x2000,
+                ending: 0x1fff
+            })
+        );
+        let mut d = MemoryDeviceMappedAddress::new(0x20);
+        assert!(d.try_set_address_range(1, 0).is_err());
+        assert!(d.try_set_address_range(0, 0).is_ok());
+    }
+
+    #[test]
+    fn test_byte_length() {
+        let mut b = BiosInformation::new(0);
+        b.set_vendor("Vendor");
+        let mut o = OemStrings::new(11);
+        o.add_string("OEM string");
+        o.add_string("Another");
+        let mut c = MemoryChannel::new(37);
+        c.add_device(1, 0x20).unwrap();
+        let payload = [1, 2, 3];
+        let structures: [&dyn SmbiosStructure; 8] = [
+            &EntryPoint::new(0x100, 0x1000),
+            &b,
+            &SystemInformation::new(1),
+            &o,
+            &OemStrings::new(12),
+            &SystemBootInformation::new(32, BootStatus::PreviouslyRequestedImage(&payload))
+                .unwrap(),
+            &c,
+            &EndOfTable::new(127),
+        ];
+        for s in structures {
+            let mut output = vec![];
+            s.serialize(&mut output);
+            assert_eq!(s.byte_length(), output.len());
+        }
+    }
+
+    #[test]
+    fn test_type0() {
+        let expected = [
+            0u8, 0x14, 0x1, 0x1, 0x1, 0x2, 0, 0, 0x3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, b'S', b'y',
+            b's', b't', b'e', b'm', b' ', b'B', b'I', b'O', b'S', b' ', b'V', b'e', b'n', b'd',
+            b'o', b'r', 0, b'4', b'.', b'0', b'4', 0, b'0', b'0', b'/', b'0', b'0', b'/', b'0',
+            b'0', b'0', b'0', 0, 0,
+        ];
+
+        let mut output = vec![];
+        let mut b = BiosInformation::new(257);
+        b.set_vendor("System BIOS Vendor");
+        b.set_bios_version("4.04");
+        b.set_bios_release_date("00/00/0000");
+
+        b.serialize(&mut output);
+        assert_eq!(expected.as_slice(), &output);
+    }
+
+    #[test]
+    fn test_type0_rom_size() {
+        let sizes = [
+            (64, Ok(0)),
+            (128, Ok(1)),
+            (1024, Ok(15)),
+            (4096, Ok(63)),
+            (8192, Ok(127)),
+            (16320, Ok(254)),
+            (16384, Err(RomSizeError::TooLargeForLegacyField)),
+            (0, Err(RomSizeError::NotMultipleOf64Kib)),
+            (100, Err(RomSizeError::NotMultipleOf64Kib)),
+        ];
+        for (kib, encoded) in sizes {
+            assert_eq!(RomSizeEncoder::from_kib(kib), encoded);
+            if let Ok(n) = encoded {
+                assert_eq!(RomSizeEncoder::to_kib(n), kib);
+            }
+        }
+
+        let mut output = vec![];
+        let mut b = BiosInformation::new(0);
+        b.set_bios_rom_size_kib(4096).unwrap();
+        b.serialize(&mut output);
+        assert_eq!(output[9], 63);
+    }
+
+    #[test]
+    fn test_bios_characteristics() {
+        assert_eq!(BiosCharacteristics::EddSpecSupported.bits(), 1 << 19);
+        assert_eq!(
+            BiosCharacteristics::Int13hJapaneseFloppyNec9800.bits(),
+            1 << 20
+        );
+        assert_eq!(BiosCharacteristics::NecPc98.bits(), 1 << 31);
+        assert_eq!(BiosCharacteristicsEx1::AcpiSupported.bits(), 1 << 0);
+        assert_eq!(BiosCharacteristicsEx1::I2oBootSupported.bits(), 1 << 3);
+        assert_eq!(BiosCharacteristicsEx1::Boot1394Supported.bits(), 1 << 6);
+        assert_eq!(BiosCharacteristicsEx1::SmartBatterySupported.bits(), 1 << 7);
+        assert_eq!(BiosCharacteristicsEx1::all().bits(), 0xff);
+        assert_eq!(BiosCharacteristicsEx2::all().bits(), 0x7f);
+
+        let mut output = vec![];
+        let mut b = BiosInformation::new(0);
+        b.set_characteristics_flags(
+            BiosCharacteristics::PciSupported | BiosCharacteristics::PnpSupported,
+        );
+        b.serialize(&mut output);
+        assert_eq!(&output[0xa..0x14], &[0x80, 0x02, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        let mut output = vec![];
+        b.set_all_characteristics(
+            BiosCharacteristics::Unknown,
+            BiosCharacteristicsEx1::AcpiSupported,
+            BiosCharacteristicsEx2::UefiSupported,
+        );
+        b.serialize(&mut output);
+        assert_eq!(&output[0xa..0x14], &[0x04, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x08]);
+    }
+
+    #[test]
+    fn test_type1() {
+        let expected = [
+            1u8, 0x1b, 0xff, 0x0, 0x1, 0x2, 0x3, 0x4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 8, 0x5, 0x6, b'O', b'E', b'M', b'1', 0, b'R', b'i', b'v', b'o', b's', b' ', b's',
+            b'y', b's', b't', b'e', b'm', 0, b'G', b'e', b'n', b'0', 0, b'0', b'1', b'2', b'3',
+            b'4', b'5', 0, b'S', b'K', b'U', b'1', 0, b'F', b'a', b'm', b'i', b'l', b'y', 0, 0,
+        ];
+
+        let mut output = vec![];
+        let mut b = SystemInformation::new(255);
+        b.set_manufacturer("OEM1");
+        b.set_product_name("Rivos system");
+        b.set_version("Gen0");
+        b.set_serial_number("012345");
+        b.set_sku_number("SKU1");
+        b.set_family("Family");
+        b.set_wakeup_type(WakeupType::AcPowerRestored.into());
+
+        b.serialize(&mut output);
+        assert_eq!(expected.as_slice(), output);
+    }
+
+    #[test]
+    fn test_type1_wakeup_type_from_u8() {
+        assert_eq!(WakeupType::from_u8(5), WakeupType::LanRemote);
+        assert_eq!(WakeupType::from_u8(200), WakeupType::Other(200));
+        assert_eq!(WakeupType::from_u8(200).to_u8(), 200);
+
+        let mut output = vec![];
+        let mut b = SystemInformation::new(1);
+        b.set_wakeup_type_from_u8(200);
+        b.serialize(&mut output);
+        assert_eq!(output[0x18], 200);
+    }
+
+    #[test]
+    fn test_type1_uuid() {
+        // 00112233-4455-6677-8899-aabbccddeeff
+        let uuid = [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ];
+        let mut output = vec![];
+        let mut b = SystemInformation::new(1);
+        b.set_uuid_rfc4122(uuid);
+        b.serialize(&mut output);
+        assert_eq!(
+            &output[0x8..0x18],
+            &[
+                0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+                0xee, 0xff
+            ]
+        );
+        assert_eq!(b.get_uuid_rfc4122(), uuid);
+
+        b.set_uuid_rfc4122(UUID_NOT_SETTABLE);
+        assert_eq!(b.get_uuid_rfc4122(), UUID_NOT_SETTABLE);
+    }
+
+    #[test]
+    fn test_type4() {
+        let expected = [
+            4u8, 0x32, 5, 0, 1, 3, 0xfe, 2, 0xef, 0xcd, 0xab, 0x90, 0x78, 0x56, 0x34, 0x12, 3, 0,
+            1, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 0, 0, 0, 0, 0,
+            0, 0, 0, b'S', b'o', b'c', b'k', b'e', b't', 0, b'M', b'a', b'n', b'u', b'f', 0, b'V',
+            b'e', b'r', b's', b'i', b'o', b'n', 0, 0,
+        ];
+
+        let mut output = vec![];
+        let mut p = ProcessorInformation::new(5);
+        p.set_socket_designation("Socket");
+        p.set_processor_type(ProcessorType::CentralProcessor);
+        p.set_processor_manufacturer("Manuf");
+        p.set_processor_family(ProcessorFamily::ObtainFrom2.into());
+        p.set_processor_id(0x1234_5678_90ab_cdef.into());
+        p.set_processor_version("Version");
+        p.set_external_clock(1.into());
+        p.set_processor_family2(ProcessorFamily2::RiscvRv64.into());
+
+        p.serialize(&mut output);
+        assert_eq!(expected.as_slice(), output);
+    }
+
+    #[test]
+    fn test_type4_family2_values() {
+        assert_eq!(ProcessorFamily2::ArmV7 as u16, 0x100);
+        assert_eq!(ProcessorFamily2::ArmV8 as u16, 0x101);
+        assert_eq!(ProcessorFamily2::ArmV9 as u16, 0x102);
+        assert_eq!(ProcessorFamily2::Sh3 as u16, 0x104);
+        assert_eq!(ProcessorFamily2::Arm as u16, 0x118);
+        assert_eq!(ProcessorFamily2::VideoProcessor as u16, 0x1f4);
+        assert_eq!(ProcessorFamily2::RiscvRv128 as u16, 0x202);
+        assert_eq!(ProcessorFamily2::LoongArch as u16, 0x258);
+        assert_eq!(ProcessorFamily2::DualCoreLoongson2K2xxx as u16, 0x262);
+
+        let mut output = vec![];
+        let mut p = ProcessorInformation::new(5);
+        p.set_processor_family_value(ProcessorFamily2::ArmV8.into())
+            .unwrap();
+        p.serialize(&mut output);
+        assert_eq!(&output[0x28..0x2a], &[0x01, 0x01]);
+    }
+
+    #[test]
+    fn test_type4_speeds() {
+        let mut output = vec![];
+        let mut p = ProcessorInformation::new(5);
+        p.set_external_clock_mhz(100);
+        p.set_speeds_mhz(3000, 2400).unwrap();
+        p.serialize(&mut output);
+        // External clock, max speed and current speed at 0x12, 0x14 and 0x16
+        assert_eq!(&output[0x12..0x18], &[100, 0, 0xb8, 0xb, 0x60, 0x9]);
+
+        let err = Err(SmbiosError::SpeedRangeInvalid {
+            max: 3000,
+            current: 3200,
+        });
+        assert_eq!(p.set_speeds_mhz(3000, 3200), err);
+        assert_eq!(p.set_current_speed_mhz(3200), err);
+        assert_eq!(
+            p.set_max_speed_mhz(2000),
+            Err(SmbiosError::SpeedRangeInvalid {
+                max: 2000,
+                current: 2400
+            })
+        );
+        // Nothing changes when the speeds are rejected
+        let mut rejected = vec![];
+        p.serialize(&mut rejected);
+        assert_eq!(output, rejected);
+
+        // An unknown speed isn't checked
+        p.set_max_speed_mhz(0).unwrap();
+        p.set_current_speed_mhz(3200).unwrap();
+        p.set_speeds_mhz(3000, 0).unwrap();
+    }
+
+    #[test]
+    fn test_type4_family_value() {
+        let mut output = vec![];
+        let mut p = ProcessorInformation::new(5);
+        p.set_processor_family_value(ProcessorFamily::Other.into())
+            .unwrap();
+        assert_eq!(p.get_effective_processor_family(), 1);
+        p.serialize(&mut output);
+        assert_eq!(output[6], 1);
+
+        let mut output = vec![];
+        p.set_processor_family_value(ProcessorFamily2::RiscvRv64.into())
+            .unwrap();
+        assert_eq!(p.get_effective_processor_family(), 0x201);
+        p.serialize(&mut output);
+        assert_eq!(output[6], 0xfe);
+        assert_eq!(&output[0x28..0x2a], &[0x01, 0x02]);
+
+        // Families this crate has no name for can still be set
+        let mut output = vec![];
+        p.set_processor_family_value(ProcessorFamilyValue::Byte(0x3a))
+            .unwrap();
+        assert_eq!(p.get_effective_processor_family(), 0x3a);
+        p.serialize(&mut output);
+        assert_eq!(output[6], 0x3a);
+        p.set_processor_family_value(ProcessorFamilyValue::Word(0x1234))
+            .unwrap();
+        assert_eq!(p.get_effective_processor_family(), 0x1234);
+
+        for bad in [
+            ProcessorFamilyValue::Byte(0xfe),
+            ProcessorFamilyValue::Word(0xfffe),
+            ProcessorFamilyValue::Word(0xffff),
+        ] {
+            assert!(matches!(
+                p.set_processor_family_value(bad),
+                Err(SmbiosError::InvalidProcessorFamily { .. })
+            ));
+        }
+        // A rejected value leaves the family unchanged
+        assert_eq!(p.get_effective_processor_family(), 0x1234);
+    }
+
+    #[test]
+    fn test_type7_cache_sizes() {
+        for (bytes, size, size2) in [
+            (512 << 10, 0x0200u16, 0x0000_0200u32),
+            (16 << 20, 0x4000, 0x0000_4000),
+            (64 << 20, 0x8400, 0x8000_0400),
+            (512 << 20, 0xa000, 0x8000_2000),
+            (4 << 30, 0xffff, 0x0040_0000),
+        ] {
+            let mut output = vec![];
+            let mut c = CacheInformation::new_l3_unified(7);
+            c.set_installed_cache_size_bytes(bytes);
+            c.set_maximum_cache_size_bytes(bytes);
+            c.serialize(&mut output);
+            assert_eq!(&output[0x07..0x09], &size.to_le_bytes());
+            assert_eq!(&output[0x09..0x0b], &size.to_le_bytes());
+            assert_eq!(&output[0x13..0x17], &size2.to_le_bytes());
+            assert_eq!(&output[0x17..0x1b], &size2.to_le_bytes());
+            assert_eq!(c.get_installed_cache_size_bytes(), bytes);
+            assert_eq!(c.get_maximum_cache_size_bytes(), bytes);
+        }
+
+        // Sizes which don't fit are saturated, rather than wrapping into the granularity bit
+        let largest = 0x7fff_ffffu64 << 16;
+        for bytes in [largest, largest + 1, 1 << 47, u64::MAX] {
+            let mut output = vec![];
+            let mut c = CacheInformation::new_l3_unified(7);
+            c.set_installed_cache_size_bytes(bytes);
+            c.serialize(&mut output);
+            assert_eq!(&output[0x09..0x0b], &[0xff, 0xff]);
+            assert_eq!(&output[0x17..0x1b], &[0xff, 0xff, 0xff, 0xff]);
+            assert_eq!(c.get_installed_cache_size_bytes(), largest);
+        }
+
+        // Without the 32-bit field, the 16-bit one is used
+        let mut c = CacheInformation::new(7);
+        c.set_installed_size(0x8002.into());
+        assert_eq!(c.get_installed_cache_size_bytes(), 128 << 10);
+        c.set_installed_size(0x0020.into());
+        assert_eq!(c.get_installed_cache_size_bytes(), 32 << 10);
+    }
+
+    #[test]
+    fn test_type7_presets() {
+        let mut output = vec![];
+        let c = CacheInformation::new_l2_unified(5);
+        c.serialize(&mut output);
+
+        // The configuration is at offset 5, and the level is stored as level - 1
+        let config = CacheConfiguration(u16::from_le_bytes([output[5], output[6]]));
+        assert_eq!(config.level(), 1);
+        assert!(config.enabled());
+        assert_eq!(config.location(), 0);
+        assert_eq!(config.op_mode(), 1);
+        assert_eq!(output[0x11], SystemCacheType::Unified as u8);
+
+        let mut output = vec![];
+        CacheInformation::new_l1_instruction(6).serialize(&mut output);
+        assert_eq!(&output[5..7], &[0x80, 0x01]);
+        assert_eq!(output[0x11], SystemCacheType::Instruction as u8);
+
+        let mut output = vec![];
+        CacheInformation::new_l3_unified(7).serialize(&mut output);
+        assert_eq!(&output[5..7], &[0x82, 0x01]);
+    }
+
+    #[test]
+    fn test_type7_risc_v_presets() {
+        let mut output = vec![];
+        let c = CacheInformation::new_risc_v_l1_data(0x700, 32, 8);
+        c.serialize(&mut output);
+
+        // L1, internal, enabled, write-back
+        assert_eq!(&output[5..7], &[0x80, 0x01]);
+        // 32 KiB in both size fields, in 1 KiB units
+        assert_eq!(&output[0x07..0x0b], &[0x20, 0, 0x20, 0]);
+        assert_eq!(&output[0x13..0x1b], &[0x20, 0, 0, 0, 0x20, 0, 0, 0]);
+        // Synchronous SRAM (bit 5)
+        assert_eq!(&output[0x0b..0x0f], &[0x20, 0, 0x20, 0]);
+        assert_eq!(output[0x10], EccType::None as u8);
+        assert_eq!(output[0x11], SystemCacheType::Data as u8);
+        assert_eq!(output[0x12], Associativity::SetAssociative8Way as u8);
+
+        let mut output = vec![];
+        CacheInformation::new_risc_v_l1_instruction(0x701, 32, 4).serialize(&mut output);
+        assert_eq!(output[0x11], SystemCacheType::Instruction as u8);
+        assert_eq!(output[0x12], Associativity::SetAssociative4Way as u8);
+
+        let mut output = vec![];
+        CacheInformation::new_risc_v_l2_unified(0x702, 2048, 6).serialize(&mut output);
+        assert_eq!(&output[5..7], &[0x81, 0x01]);
+        assert_eq!(&output[0x07..0x09], &[0x00, 0x08]);
+        assert_eq!(output[0x11], SystemCacheType::Unified as u8);
+        assert_eq!(output[0x12], Associativity::Other as u8);
+    }
+
+    #[test]
+    fn test_ecc_type() {
+        let mut output = vec![];
+        let mut c = CacheInformation::new(7);
+        c.set_error_correction_type(EccType::SingleBitEcc);
+        c.serialize(&mut output);
+        let mut p = PhysicalMemoryArray::new(16);
+        p.set_memory_error_correction(EccType::SingleBitEcc.into());
+        p.serialize(&mut output);
+
+        // Type 7 ECC at offset 0x10, Type 16 ECC at offset 6
+        assert_eq!(output[0x10], 5);
+        assert_eq!(output[0x1b + 2 + 6], 5);
+
+        for e in [
+            EccType::Other,
+            EccType::Unknown,
+            EccType::None,
+            EccType::Parity,
+            EccType::SingleBitEcc,
+            EccType::MultiBitEcc,
+            EccType::Crc,
+        ] {
+            let converted = ErrorCorrectionType::from(e);
+            assert_eq!(e as u8, converted as u8);
+            assert!(e == converted);
+        }
+        assert!(EccType::Parity != ErrorCorrectionType::Crc);
+    }
+
+    #[test]
+    fn test_type_predicates() {
+        assert!(EccType::SingleBitEcc.is_error_correcting());
+        assert!(EccType::MultiBitEcc.is_error_correcting());
+        for e in [
+            EccType::None,
+            EccType::Parity,
+            EccType::Crc,
+            EccType::Unknown,
+        ] {
+            assert!(!e.is_error_correcting());
+        }
+
+        assert!(ErrorCorrectionType::SingleBitEcc.corrects_single_bit());
+        assert!(ErrorCorrectionType::MultiBitEcc.corrects_single_bit());
+        assert!(!ErrorCorrectionType::Parity.corrects_single_bit());
+        assert!(!ErrorCorrectionType::Crc.corrects_single_bit());
+
+        for ty in [MemoryType::Ddr, MemoryType::Ddr2FbDimm, MemoryType::Ddr5] {
+            assert!(ty.is_ddr() && !ty.is_lpddr() && !ty.is_hbm());
+        }
+        for ty in [MemoryType::Lpddr, MemoryType::Lpddr4, MemoryType::Lpddr5x] {
+            assert!(ty.is_lpddr() && !ty.is_ddr() && !ty.is_hbm());
+        }
+        for ty in [MemoryType::Hbm, MemoryType::Hbm2, MemoryType::Hbm3] {
+            assert!(ty.is_hbm() && !ty.is_ddr() && !ty.is_lpddr());
+        }
+        for ty in [MemoryType::Sdram, MemoryType::Gddr6, MemoryType::Unknown] {
+            assert!(!ty.is_ddr() && !ty.is_lpddr() && !ty.is_hbm());
+        }
+
+        for f in [FormFactor::Dimm, FormFactor::Sodimm, FormFactor::Lpcamm2] {
+            assert!(f.is_module());
+        }
+        for f in [FormFactor::Chip, FormFactor::RowOfChips, FormFactor::Die] {
+            assert!(!f.is_module());
+        }
+
+        for slot in [
+            SlotType::PcieGen2Sff8639,
+            SlotType::PcieMini76,
+            SlotType::PcieGen5Sff8639,
+            SlotType::Pcie,
+            SlotType::PcieGen4x16,
+            SlotType::PcieGen6,
+        ] {
+            assert!(slot.is_pcie());
+        }
+        for slot in [
+            SlotType::Pci,
+            SlotType::PciX,
+            SlotType::M2Socket3,
+            SlotType::OcpNic3Sff,
+            SlotType::Pc98Card,
+            SlotType::EdsffE1,
+        ] {
+            assert!(!slot.is_pcie());
+        }
+    }
+
+    #[test]
+    fn test_type9() {
+        let expected = [
+            9u8, 0x18, 3, 0, 1, 0xc3, 0xd, 4, 4, 1, 0, 0, 0, 0, 0, 0x80, 0, 0xd, 0, 0, 0xd, 0xb8,
+            0xb, 4, b'S', b'l', b'o', b't', b'1', 0, 0,
+        ];
+
+        let mut output = vec![];
+        let mut s = SystemSlots::new(3);
+        s.set_slot_designation("Slot1");
+        s.set_slot_type(SlotType::PcieGen5x16);
+        s.set_slot_data_bus_width(SlotWidth::Widthx16);
+        s.set_current_usage(CurrentUsage::InUse);
+        s.set_slot_length(SlotLength::LongLength);
+        s.set_slot_id(1.into());
+        s.set_bus_number(0x80);
+        s.set_data_bus_width(13);
+        s.set_slot_physical_width(SlotWidth::Widthx16);
+        s.set_slot_pitch_hundredths_mm(3000);
+        s.set_slot_height(SlotHeight::LowProfile);
+        s.serialize(&mut output);
+
+        assert_eq!(expected.as_slice(), output);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut b = BiosInformation::new(0);
+        b.set_vendor("Rivos");
+        b.set_bios_version("1.0");
+        let s = format!("{}", b);
+        assert!(s.starts_with("Type 0 (BIOS Information)"));
+        assert!(s.contains("vendor=\"Rivos\""));
+        assert!(s.contains("version=\"1.0\""));
+        assert!(s.contains("date=\"\""));
+
+        let mut m = MemoryDevice::new(0x20);
+        m.set_device_locator("DIMM_A1");
+        m.set_memory_type(MemoryType::Ddr5);
+        m.set_memory_size(Some(gb(32)));
+        let s = format!("{}", m);
+        assert!(s.contains("locator=\"DIMM_A1\""));
+        assert!(s.contains("type=Ddr5"));
+        assert!(s.contains("size=32768 MB"));
+        m.set_speed_mts(70000);
+        assert!(format!("{}", m).contains("speed=70000 MT/s"));
+
+        let mut p = PhysicalMemoryArray::new(10);
+        p.set_memory_capacity(tb(3));
+        assert!(format!("{}", p).contains("capacity=3145728 MB"));
+
+        let c = CacheInformation::new_risc_v_l2_unified(7, 2048, 16);
+        assert!(format!("{}", c).contains("installed_size=2048 KB"));
+
+        let mut p = ProcessorInformation::new(4);
+        p.set_core_count(8);
+        assert!(format!("{}", p).contains("cores=8"));
+        p.set_core_count(0xff);
+        p.set_core_count2(300.into());
+        assert!(format!("{}", p).contains("cores=300"));
+    }
+
+    #[test]
+    fn test_type20_interleave() {
+        let mut output = vec![];
+        for (handle, position) in [(0x40, 1), (0x41, 2)] {
+            let mut m = MemoryDeviceMappedAddress::new(handle);
+            m.set_address_range(0, gb(32) - 1);
+            m.set_memory_device_handle((handle - 0x20).into());
+            m.set_partition_row_position(PartitionRowPosition::known(1));
+            m.set_interleaved_mapping(
+                InterleavePosition::from_u8(position),
+                InterleaveDataDepth::from_u8(1),
+            );
+            m.serialize(&mut output);
+        }
+
+        // partition row position, interleave position and depth at offset 0x10
+        assert_eq!(&output[0x10..0x13], &[1, 1, 1]);
+        assert_eq!(&output[0x25 + 0x10..0x25 + 0x13], &[1, 2, 1]);
+
+        let m = MemoryDeviceMappedAddress::new(0x42);
+        let mut output = vec![];
+        m.serialize(&mut output);
+        assert_eq!(&output[0x10..0x13], &[0xff, 0, 0]);
+    }
+
+    #[test]
+    fn test_type32_owned() {
+        let payload = [1, 2, 3];
+        let borrowed =
+            SystemBootInformation::new(32, BootStatus::PreviouslyRequestedImage(&payload)).unwrap();
+        let owned = OwnedSystemBootInformation::new(
+            32,
+            OwnedBootStatus::PreviouslyRequestedImage(vec![1, 2, 3]),
+        )
+        .unwrap();
+        let mut expected = vec![];
+        borrowed.serialize(&mut expected);
+        let mut output = vec![];
+        owned.serialize(&mut output);
+        assert_eq!(expected, output);
+        assert_eq!(owned.byte_length(), output.len());
+
+        // Converting keeps the payload after the borrowed data is gone
+        let owned = {
+            let payload = [0xaa, 0xbb];
+            OwnedSystemBootInformation::from(
+                SystemBootInformation::new(32, BootStatus::VendorSpecific(130, &payload)).unwrap(),
+            )
+        };
+        let mut output = vec![];
+        owned.serialize(&mut output);
+        assert_eq!(&output[0xa..0xd], &[130, 0xaa, 0xbb]);
+
+        let status = OwnedBootStatus::ProductSpecific(192, vec![0; MAX_BOOT_STATUS_PAYLOAD + 1]);
+        assert_eq!(
+            OwnedSystemBootInformation::new(32, status).unwrap_err(),
+            SmbiosError::StructureTooLarge
+        );
+    }
+
+    #[test]
+    fn test_type32_payload_limit() {
+        let payload = [0u8; 250];
+        let status = BootStatus::VendorSpecific(128, &payload);
+        assert_eq!(
+            SystemBootInformation::new(32, status).unwrap_err(),
+            SmbiosError::StructureTooLarge
+        );
+
+        let mut output = vec![];
+        let payload = [0u8; MAX_BOOT_STATUS_PAYLOAD];
+        let status = BootStatus::VendorSpecific(128, &payload);
+        let b = SystemBootInformation::new(32, status).unwrap();
+        b.serialize(&mut output);
+        assert_eq!(output[1], 255);
+        assert_eq!(output.len(), 257);
+    }
+
+    #[test]
+    fn test_type32_status_codes() {
+        for code in [127, 192] {
+            assert_eq!(
+                SystemBootInformation::new(32, BootStatus::VendorSpecific(code, &[])).unwrap_err(),
+                SmbiosError::InvalidBootStatusCode { code }
+            );
+        }
+        assert_eq!(
+            SystemBootInformation::new(32, BootStatus::ProductSpecific(191, &[])).unwrap_err(),
+            SmbiosError::InvalidBootStatusCode { code: 191 }
+        );
+        let status = OwnedBootStatus::VendorSpecific(200, vec![]);
+        assert_eq!(
+            OwnedSystemBootInformation::new(32, status).unwrap_err(),
+            SmbiosError::InvalidBootStatusCode { code: 200 }
+        );
+
+        let b = SystemBootInformation::new(32, BootStatus::ProductSpecific(255, &[1])).unwrap();
+        let mut output = vec![];
+        b.serialize(&mut output);
+        assert_eq!(&output[0xa..0xc], &[255, 1]);
+    }
+
+    #[test]
+    fn test_type37() {
+        let expected = [37u8, 13, 0x37, 0, 3, 8, 2, 4, 0x20, 0, 4, 0x21, 0, 0, 0];
+
+        let mut output = vec![];
+        let mut c = MemoryChannel::new(0x37);
+        c.set_channel_type(MemoryChannelType::RamBus);
+        c.set_maximum_channel_load(8);
+        c.add_device(4, 0x20).unwrap();
+        c.add_device(4, 0x21).unwrap();
+        c.serialize(&mut output);
+
+        assert_eq!(expected.as_slice(), output);
+    }
+
+    #[test]
+    fn test_type37_device_limit() {
+        let mut c = MemoryChannel::new(0x37);
+        for handle in 0..82 {
+            c.add_device(1, handle).unwrap();
+        }
+        assert_eq!(c.add_device(1, 82), Err(SmbiosError::StructureTooLarge));
+        let mut output = vec![];
+        c.serialize(&mut output);
+        assert_eq!((output[1], output[6]), (7 + 3 * 82, 82));
+        assert_eq!(c.byte_length(), output.len());
+    }
+
+    #[test]
+    fn test_type43_characteristics() {
+        let mut output = vec![];
+        let mut t = TpmDevice::new(0x43);
+        t.set_tpm_characteristics(
+            TpmDeviceCharacteristics::FamilyConfigurableViaFirmwareUpdate
+                | TpmDeviceCharacteristics::FamilyConfigurableViaPlatformSoftware,
+        );
+        t.serialize(&mut output);
+
+        // characteristics at offset 0x13
+        assert_eq!(&output[0x13..0x1b], &[0x18, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_type4_type44_risc_v() {
+        let mut output = vec![];
+        let mut p = ProcessorInformation::new_risc_v(4, Xlen::Xlen64).unwrap();
+        let mut h = RiscvProcessorAdditionalInformation::new(44);
+        h.set_hart_id(3.into());
+        h.set_xlen(Xlen::Xlen64);
+        let h = p.link_to_hart(&h);
+        p.set_processor_version("RV64GC");
+        p.serialize(&mut output);
+        h.serialize(&mut output);
+
+        assert_eq!(&output[0..6], &[4, 0x32, 4, 0, 0, 3]);
+        assert_eq!(output[6], 0xfe);
+        assert_eq!(&output[0x28..0x2a], &[0x01, 0x02]);
+
+        // Type 44 follows the Type 4 strings
+        let t44 = &output[0x32 + 8..];
+        assert_eq!(&t44[0..6], &[44, 0x74, 44, 0, 4, 0]);
+        assert_eq!(t44[0x9], 3);
+        assert_eq!(t44[0x6f], Xlen::Xlen64 as u8);
+    }
+
+    #[test]
+    fn test_type44_vendor_and_arch() {
+        let mut output = vec![];
+        let mut h = RiscvProcessorAdditionalInformation::new(44);
+        h.set_vendor_and_arch(MVENDORID_SIFIVE, (1 << 64) | MARCHID_BOOM, 0x20);
+        h.serialize(&mut output);
+
+        // mvendorid, marchid and mimplid at offsets 0x1a, 0x2a and 0x3a
+        assert_eq!(&output[0x1a..0x1c], &[0x89, 0x04]);
+        assert_eq!(
+            &output[0x2a..0x3a],
+            &[2, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(output[0x3a], 0x20);
+    }
+
+    #[test]
+    fn test_type44_revision() {
+        let mut output = vec![];
+        let mut h = RiscvProcessorAdditionalInformation::new(44);
+        assert_eq!(h.get_revision(), None);
+        h.set_revision(RiscvRevision::Rev1);
+        h.serialize(&mut output);
+
+        assert_eq!(&output[6..9], &[0x00, 0x01, 0x6e]);
+        assert_eq!(h.get_revision(), Some(RiscvRevision::Rev1));
+
+        h.set_structure_length(0x20);
+        assert_eq!(h.get_revision(), None);
+    }
 
     #[test]
-    fn test_type0() {
+    fn test_type44_loongarch() {
         let expected = [
-            0u8, 0x14, 0x1, 0x1, 0x1, 0x2, 0, 0, 0x3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, b'S', b'y',
-            b's', b't', b'e', b'm', b' ', b'B', b'I', b'O', b'S', b' ', b'V', b'e', b'n', b'd',
-            b'o', b'r', 0, b'4', b'.', b'0', b'4', 0, b'0', b'0', b'/', b'0', b'0', b'/', b'0',
-            b'0', b'0', b'0', 0, 0,
+            44u8, 0x27, 0x2c, 0, 0x04, 0, 0x00, 0x01, 0x21, 3, 0, 0, 0, 0, 0, 0, 0, 1, 0x14, 0, 0,
+            0, 0, 0, 0, 0, 0xc0, 0x14, 0, 0, 0, 0, 0, 0, 0x34, 0, 0, 0, 0x09, 0, 0,
         ];
 
+        // The boot core of a Loongson 3A5000, which is LA64 with LSX and LASX
         let mut output = vec![];
-        let mut b = BiosInformation::new(257);
-        b.set_vendor("System BIOS Vendor");
-        b.set_bios_version("4.04");
-        b.set_bios_release_date("00/00/0000");
+        let mut h = LoongArchProcessorAdditionalInformation::new(0x2c);
+        h.set_referenced_handle(4.into());
+        h.set_revision(LoongArchRevision::Rev1);
+        h.set_core_id(3.into());
+        h.set_boot_core(1);
+        h.set_vendor_id(0x14.into());
+        h.set_cpu_id(0x14c0.into());
+        h.set_isa_supported(
+            LoongArchIsaFlags::La64 | LoongArchIsaFlags::Lsx | LoongArchIsaFlags::Lasx,
+        );
+        h.set_privilege_levels_supported(
+            LoongArchPrivilegeLevels::Plv0 | LoongArchPrivilegeLevels::Plv3,
+        );
+        h.serialize(&mut output);
+        assert_eq!(expected.as_slice(), output);
+        assert_eq!(h.get_revision(), Some(LoongArchRevision::Rev1));
+    }
 
-        b.serialize(&mut output);
-        assert_eq!(expected.as_slice(), &output);
+    #[test]
+    fn test_xlen() {
+        for bits in [32, 64, 128] {
+            assert_eq!(Xlen::from_bits(bits).unwrap().bits(), bits);
+        }
+        assert!(Xlen::from_bits(16).is_none());
+        assert_eq!(Xlen::Unsupported.bits(), 0);
+        assert!(Xlen::from_misa_mxl(0).is_none());
+
+        // RV32IMAC, RV64GC and RV128I
+        for (misa, xlen) in [
+            ((1 << 62) | 0x1105, Xlen::Xlen32),
+            ((2 << 62) | 0x14112d, Xlen::Xlen64),
+            ((3 << 62) | 0x100, Xlen::Xlen128),
+        ] {
+            let mut output = vec![];
+            let mut h = RiscvProcessorAdditionalInformation::new(44);
+            h.set_xlen_from_misa(misa);
+            h.serialize(&mut output);
+            assert_eq!(&output[0x6f..0x71], &[xlen as u8, xlen as u8]);
+        }
     }
 
     #[test]
-    fn test_type1() {
+    fn test_type44_u64_ids() {
+        let mut output = vec![];
+        let mut h = RiscvProcessorAdditionalInformation::new(44);
+        h.set_mhartid_u128(u128::MAX);
+        h.set_mhartid_u64(42);
+        h.set_mimplid_u64(u64::MAX);
+        h.serialize(&mut output);
+
+        // hart_id at offset 0x9
+        assert_eq!(
+            &output[0x9..0x19],
+            &[42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(&output[0x3a..0x42], &[0xff; 8]);
+        assert_eq!(&output[0x42..0x4a], &[0; 8]);
+    }
+
+    #[test]
+    fn test_firmware_version() {
+        let cases = [
+            (
+                FirmwareVersion::SemVer {
+                    major: 1,
+                    minor: 22,
+                    patch: 3,
+                },
+                "1.22.3",
+                FirmwareVersionFormat::FreeForm,
+            ),
+            (
+                FirmwareVersion::MajorMinor { major: 2, minor: 7 },
+                "2.7",
+                FirmwareVersionFormat::MajorMinor,
+            ),
+            (
+                FirmwareVersion::Freeform("rev B".into()),
+                "rev B",
+                FirmwareVersionFormat::FreeForm,
+            ),
+            (
+                FirmwareVersion::HexEncoded(0xdead_00be),
+                "0xDEAD00BE",
+                FirmwareVersionFormat::Hex32,
+            ),
+        ];
+        for (version, s, format) in cases {
+            assert_eq!(version.to_smbios_string(), s);
+            assert_eq!(version.format_hint(), format);
+        }
+    }
+
+    #[test]
+    fn test_type45() {
         let expected = [
-            1u8, 0x1b, 0xff, 0x0, 0x1, 0x2, 0x3, 0x4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-            0, 8, 0x5, 0x6, b'O', b'E', b'M', b'1', 0, b'R', b'i', b'v', b'o', b's', b' ', b's',
-            b'y', b's', b't', b'e', b'm', 0, b'G', b'e', b'n', b'0', 0, b'0', b'1', b'2', b'3',
-            b'4', b'5', 0, b'S', b'K', b'U', b'1', 0, b'F', b'a', b'm', b'i', b'l', b'y', 0, 0,
+            45u8, 0x1a, 0x2d, 0, 1, 2, 1, 0, 0, 0, 3, 0, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0, 4, 1, 4, 0,
+            b'B', b'M', b'C', 0, b'3', b'.', b'1', 0, b'A', b'c', b'm', b'e', 0, 0,
         ];
 
         let mut output = vec![];
-        let mut b = SystemInformation::new(255);
-        b.set_manufacturer("OEM1");
-        b.set_product_name("Rivos system");
-        b.set_version("Gen0");
-        b.set_serial_number("012345");
-        b.set_sku_number("SKU1");
-        b.set_family("Family");
-        b.set_wakeup_type(WakeupType::AcPowerRestored);
+        let mut f = FirmwareInventoryInformation::new(45);
+        f.set_component_name("BMC");
+        f.set_firmware_version(FirmwareVersion::MajorMinor { major: 3, minor: 1 });
+        f.set_manufacturer("Acme");
+        f.set_image_size(Some(0x10000));
+        f.set_characteristics(FirmwareInventoryCharacteristics::Updatable);
+        f.set_state(FirmwareInventoryState::Enabled);
+        f.add_associated_component(4).unwrap();
+        f.serialize(&mut output);
+        assert_eq!(expected.as_slice(), output);
+        assert_eq!(f.byte_length(), output.len());
 
-        b.serialize(&mut output);
+        // Setting a string again replaces it
+        f.set_component_name("BMC");
+        f.set_firmware_version(FirmwareVersion::MajorMinor { major: 3, minor: 1 });
+        f.set_manufacturer("Acme");
+        let mut output = vec![];
+        f.serialize(&mut output);
         assert_eq!(expected.as_slice(), output);
     }
 
     #[test]
-    fn test_type4() {
-        let expected = [
-            4u8, 0x32, 5, 0, 1, 3, 0xfe, 2, 0xef, 0xcd, 0xab, 0x90, 0x78, 0x56, 0x34, 0x12, 3, 0,
-            1, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 0, 0, 0, 0, 0,
-            0, 0, 0, b'S', b'o', b'c', b'k', b'e', b't', 0, b'M', b'a', b'n', b'u', b'f', 0, b'V',
-            b'e', b'r', b's', b'i', b'o', b'n', 0, 0,
-        ];
+    fn test_type45_component_limit() {
+        let mut f = FirmwareInventoryInformation::new(45);
+        for handle in 0..115 {
+            f.add_associated_component(handle).unwrap();
+        }
+        assert_eq!(
+            f.add_associated_component(115),
+            Err(SmbiosError::StructureTooLarge)
+        );
+        let mut output = vec![];
+        f.serialize(&mut output);
+        assert_eq!((output[1], output[0x17]), (0x18 + 2 * 115, 115));
+        assert_eq!(f.byte_length(), output.len());
+    }
+
+    #[test]
+    fn test_associativity() {
+        for v in 0..=0xff {
+            match Associativity::from_u8(v) {
+                Some(a) => {
+                    assert_eq!(a as u8, v);
+                    if let Some(ways) = a.ways() {
+                        assert_eq!(Associativity::from_ways(ways).unwrap() as u8, v);
+                    }
+                }
+                None => assert!(v == 0 || v > 14),
+            }
+        }
+        assert_eq!(
+            Associativity::from_ways(8).map(|a| a as u8),
+            Some(Associativity::SetAssociative8Way as u8)
+        );
+        assert_eq!(Associativity::FullyAssociative.ways(), None);
+        assert!(Associativity::from_ways(3).is_none());
+    }
 
+    #[test]
+    fn test_slot_characteristics() {
         let mut output = vec![];
-        let mut p = ProcessorInformation::new(5);
-        p.set_socket_designation("Socket");
-        p.set_processor_type(ProcessorType::CentralProcessor);
-        p.set_processor_manufacturer("Manuf");
-        p.set_processor_family(ProcessorFamily::ObtainFrom2);
-        p.set_processor_id(0x1234_5678_90ab_cdef.into());
-        p.set_processor_version("Version");
-        p.set_external_clock(1.into());
-        p.set_processor_family2(ProcessorFamily2::RiscvRv64);
+        let mut s = SystemSlots::new(9);
+        s.set_slot_characteristics1_flags(SlotCharacteristics1::Provides3_3Volts);
+        s.set_slot_characteristics2_flags(SlotCharacteristics2::Cxl2Supported);
+        s.add_slot_characteristics2_flags(SlotCharacteristics2::Cxl3Supported);
+        s.serialize(&mut output);
 
-        p.serialize(&mut output);
-        assert_eq!(expected.as_slice(), output);
+        // Characteristics 1 and 2 at offsets 0xb and 0xc
+        assert_eq!(&output[0xb..0xd], &[0x04, 0xc0]);
+    }
+
+    #[test]
+    fn test_slot_type() {
+        assert_eq!(SlotType::M2Socket3 as u8, 0x17);
+        assert_eq!(SlotType::PcieGen4Sff8639 as u8, 0x24);
+        assert_eq!(SlotType::OcpNic3Sff as u8, 0x26);
+        assert_eq!(SlotType::CxlFlexbus1 as u8, 0x30);
+        assert_eq!(SlotType::PcieGen5 as u8, 0xbe);
+        assert_eq!(SlotType::PcieGen5x16 as u8, 0xc3);
+        assert_eq!(SlotType::PcieGen6 as u8, 0xc4);
+        assert_eq!(SlotType::EdsffE3 as u8, 0xc6);
     }
 
     #[test]
@@ -610,4 +4638,641 @@ mod tests {
 
         assert_eq!(expected.as_slice(), output);
     }
+
+    #[test]
+    fn test_type16_location() {
+        let mut output = vec![];
+        let mut p = PhysicalMemoryArray::new(10);
+        p.set_location(ArrayLocation::CxlAddonCard);
+        p.serialize(&mut output);
+        assert_eq!(output[4], 0xa4);
+
+        for v in 0..=0xff {
+            match ArrayLocation::from_u8(v) {
+                Ok(l) => assert_eq!(l as u8, v),
+                Err(e) => assert_eq!(e, UnknownArrayLocation(v)),
+            }
+        }
+        assert!(ArrayLocation::CxlAddonCard.is_add_on_card());
+        assert!(ArrayLocation::PciAddonCard.is_add_on_card());
+        assert!(!ArrayLocation::SystemBoard.is_add_on_card());
+        assert!(!ArrayLocation::Unknown.is_add_on_card());
+    }
+
+    #[test]
+    fn test_type16_error_information() {
+        let mut output = vec![];
+        let mut p = PhysicalMemoryArray::new(10);
+        p.set_error_information_unsupported();
+        p.serialize(&mut output);
+        assert_eq!(&output[0xb..0xd], &[0xfd, 0xff]);
+
+        let mut output = vec![];
+        p.set_error_information(None);
+        p.serialize(&mut output);
+        assert_eq!(&output[0xb..0xd], &[0xfe, 0xff]);
+
+        let mut output = vec![];
+        p.set_error_information(Some(0x30));
+        p.serialize(&mut output);
+        assert_eq!(&output[0xb..0xd], &[0x30, 0]);
+    }
+
+    #[test]
+    fn test_memory_type() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_memory_type(MemoryType::Gddr6x);
+        m.serialize(&mut output);
+        assert_eq!(output[0x12], 0x26);
+        assert_eq!(MemoryType::Lpddr5x as u8, 0x27);
+
+        for v in 0..=0xff {
+            if let Some(t) = MemoryType::from_u8(v).as_known() {
+                assert_eq!(t as u8, v);
+            }
+        }
+        assert!(matches!(
+            MemoryType::from_u8(0x27).as_known(),
+            Some(MemoryType::Lpddr5x)
+        ));
+        assert!(MemoryType::from_u8(0x15).as_known().is_none());
+        assert!(MemoryType::from_u8(0x80).as_known().is_none());
+        assert_eq!(MemoryTypeRaw::from(MemoryType::Ddr5), MemoryTypeRaw(0x22));
+    }
+
+    #[test]
+    fn test_form_factor() {
+        for v in 0..=0xff {
+            match FormFactor::from_u8(v) {
+                Ok(f) => assert_eq!(f as u8, v),
+                Err(e) => assert_eq!(e, UnknownFormFactor(v)),
+            }
+        }
+        assert!(matches!(FormFactor::from_u8(0x12), Ok(FormFactor::Lpcamm2)));
+        assert_eq!(FormFactor::from_u8(0x13), Err(UnknownFormFactor(0x13)));
+
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_memory_size(Some(gb(32)));
+        m.set_memory_type(MemoryType::Lpddr5x);
+        m.set_form_factor(FormFactor::Lpcamm2);
+        m.serialize(&mut output);
+        assert_eq!(output[0xe], 0x12);
+        assert_eq!(output[0x12], 0x27);
+
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x21);
+        m.set_device_locator("U1");
+        m.set_as_soldered_memory(gb(16), MemoryType::Lpddr5);
+        m.serialize(&mut output);
+        assert_eq!(&output[0xc..0x12], &[0, 0x40, 0xb, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_memory_technology() {
+        assert!(MemoryTechnology::Dram.is_volatile());
+        assert!(!MemoryTechnology::Dram.is_persistent());
+        assert!(MemoryTechnology::NvdimmP.is_persistent());
+        assert!(MemoryTechnology::IntelOptane.is_persistent());
+        assert!(!MemoryTechnology::Hbm2.is_persistent());
+
+        for v in 0..=0xff {
+            match MemoryTechnology::from_u8(v) {
+                Ok(t) => assert_eq!(t as u8, v),
+                Err(e) => assert_eq!(e, UnknownMemoryTechnology(v)),
+            }
+        }
+        assert_eq!(MemoryTechnology::from_u8(8), Ok(MemoryTechnology::Mram));
+    }
+
+    #[test]
+    fn test_type17_type_detail() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_memory_type(MemoryType::Ddr5);
+        m.set_type_detail_for_memory_type(MemoryType::Ddr5);
+        m.serialize(&mut output);
+        // Synchronous (bit 7) and registered (bit 13)
+        assert_eq!(&output[0x13..0x15], &[0x80, 0x20]);
+
+        let mut output = vec![];
+        m.set_type_detail_for_memory_type(MemoryType::Lpddr5);
+        m.serialize(&mut output);
+        assert_eq!(&output[0x13..0x15], &[0x80, 0x40]);
+
+        let mut output = vec![];
+        m.set_type_detail_flags(TypeDetail::DDR5_UDIMM);
+        m.serialize(&mut output);
+        assert_eq!(&output[0x13..0x15], &[0x80, 0x40]);
+    }
+
+    #[test]
+    fn test_type17_type_detail_presets() {
+        // Synchronous is bit 7; registered, unbuffered and LRDIMM are bits 13, 14 and 15, and
+        // non-volatile is bit 12
+        let presets = [
+            (
+                MemoryDevice::set_type_detail_rdimm as fn(&mut MemoryDevice),
+                [0x80, 0x20],
+            ),
+            (MemoryDevice::set_type_detail_udimm, [0x80, 0x40]),
+            (MemoryDevice::set_type_detail_lrdimm, [0x80, 0x80]),
+            (MemoryDevice::set_type_detail_sodimm, [0x80, 0x40]),
+            (MemoryDevice::set_type_detail_nvdimm, [0x80, 0x10]),
+            (MemoryDevice::set_type_detail_hbm, [0x80, 0x00]),
+        ];
+        for (preset, expected) in presets {
+            let mut output = vec![];
+            let mut m = MemoryDevice::new(0x20);
+            preset(&mut m);
+            m.serialize(&mut output);
+            assert_eq!(&output[0x13..0x15], &expected);
+        }
+
+        // A module is never both registered and unbuffered
+        for flags in [
+            TypeDetail::RDIMM,
+            TypeDetail::UDIMM,
+            TypeDetail::LRDIMM,
+            TypeDetail::SODIMM,
+        ] {
+            assert!(flags.contains(TypeDetail::Synchronous));
+            assert!(!flags.contains(TypeDetail::Registered | TypeDetail::Unbuffered));
+        }
+        assert_eq!(TypeDetail::RDIMM.bits(), TypeDetail::DDR5_RDIMM.bits());
+        assert_eq!(TypeDetail::UDIMM.bits(), TypeDetail::DDR5_UDIMM.bits());
+
+        for (ff, expected) in [
+            (FormFactor::Sodimm, TypeDetail::SODIMM),
+            (FormFactor::Dimm, TypeDetail::Synchronous),
+            (FormFactor::Die, TypeDetail::HBM),
+            (FormFactor::Simm, TypeDetail::Unknown),
+        ] {
+            assert_eq!(TypeDetail::for_form_factor(ff).bits(), expected.bits());
+        }
+    }
+
+    #[test]
+    fn test_type17_locators_from_slot_index() {
+        let mut m = MemoryDevice::new(0x20);
+        m.set_locators_from_slot_index(0, 1, 0);
+        assert_eq!(m.get_string(m.data.device_locator), "P0_D10");
+        assert_eq!(m.get_string(m.data.bank_locator), "NODE 0 CHANNEL 1 DIMM 0");
+
+        m.set_locators_from_slot_index_with_format(1, 2, 1, LocatorFormat::ChannelLetters);
+        assert_eq!(m.get_string(m.data.device_locator), "CPU1_DIMM_C1");
+        assert_eq!(m.get_string(m.data.bank_locator), "P1_Node1_Channel2_Dimm1");
+
+        m.set_locators_from_slot_index_with_format(0, 30, 0, LocatorFormat::ChannelLetters);
+        assert_eq!(m.get_string(m.data.device_locator), "P0_D300");
+
+        // The old strings are replaced rather than added to
+        let mut output = vec![];
+        m.serialize(&mut output);
+        assert_eq!(
+            &output[output[1] as usize..],
+            b"P0_D300\0NODE 0 CHANNEL 30 DIMM 0\0\0"
+        );
+    }
+
+    #[test]
+    fn test_type17_empty_bank_locator() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_device_locator("DIMM0");
+        m.set_bank_locator("BANK 0");
+        m.serialize(&mut output);
+        assert_eq!(&output[0x10..0x12], &[1, 2]);
+
+        let mut output = vec![];
+        m.set_bank_locator_empty();
+        m.serialize(&mut output);
+        assert_eq!(&output[0x10..0x12], &[1, 0]);
+        assert_eq!(&output[0x64..], b"DIMM0\0\0");
+
+        let mut output = vec![];
+        m.set_bank_locator_or_empty("BANK 0");
+        m.set_device_locator_or_empty("");
+        m.serialize(&mut output);
+        assert_eq!(&output[0x10..0x12], &[0, 1]);
+        assert_eq!(&output[0x64..], b"BANK 0\0\0");
+
+        let mut output = vec![];
+        m.set_bank_locator_or_empty("");
+        m.serialize(&mut output);
+        assert_eq!(&output[0x10..0x12], &[0, 0]);
+        assert_eq!(&output[0x64..], &[0, 0]);
+    }
+
+    #[test]
+    fn test_type17_set_technology() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_technology(MemoryTechnology::NvdimmP);
+        m.serialize(&mut output);
+        assert_eq!(output[0x28], MemoryTechnology::NvdimmP as u8);
+        assert_eq!(&output[0x29..0x2b], &[0b11_0000, 0]);
+
+        for (tech, mode) in [
+            (MemoryTechnology::Dram, OperatingMode::Volatile),
+            (
+                MemoryTechnology::NvdimmN,
+                OperatingMode::ByteAccessiblePersistent,
+            ),
+            (
+                MemoryTechnology::NvdimmF,
+                OperatingMode::BlockAccessiblePersistent,
+            ),
+            (
+                MemoryTechnology::IntelOptane,
+                OperatingMode::ByteAccessiblePersistent | OperatingMode::Volatile,
+            ),
+        ] {
+            let mut output = vec![];
+            m.set_technology(tech);
+            m.serialize(&mut output);
+            assert_eq!(output[0x28], tech as u8);
+            assert_eq!(&output[0x29..0x2b], &mode.bits().to_le_bytes());
+        }
+    }
+
+    #[test]
+    fn test_type17_module_dimensions() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_memory_type(MemoryType::Ddr5);
+        m.set_module_dimensions(64, 8).unwrap();
+        m.serialize(&mut output);
+
+        // total_width at offset 8, data_width at offset 10
+        assert_eq!(&output[8..12], &[72, 0, 64, 0]);
+
+        // Totals which overflow, or which would read as unknown, are rejected
+        let mut output = vec![];
+        assert_eq!(
+            m.set_module_dimensions(0xfff8, 8),
+            Err(SmbiosError::ModuleWidthInvalid {
+                data_width: 0xfff8,
+                ecc: 8
+            })
+        );
+        assert_eq!(
+            m.set_module_dimensions(0xfff0, 0x20),
+            Err(SmbiosError::ModuleWidthInvalid {
+                data_width: 0xfff0,
+                ecc: 0x20
+            })
+        );
+        m.serialize(&mut output);
+        assert_eq!(&output[8..12], &[72, 0, 64, 0]);
+
+        let mut output = vec![];
+        m.set_module_dimensions_unknown();
+        m.serialize(&mut output);
+        assert_eq!(&output[8..12], &[0xff, 0xff, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn test_type17_nvdimm_p() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        let size = m.get_memory_size();
+        assert_eq!(
+            m.set_as_nvdimm_p(gb(256), 101),
+            Err(SmbiosError::PercentageInvalid { percent: 101 })
+        );
+        assert_eq!(m.get_memory_size(), size);
+        m.set_as_nvdimm_p(gb(256), 25).unwrap();
+        m.serialize(&mut output);
+
+        assert_eq!(output[0xe], FormFactor::Dimm as u8);
+        assert_eq!(output[0x28], MemoryTechnology::NvdimmP as u8);
+        // size (in MB) overflows into extended size
+        assert_eq!(&output[0xc..0xe], &[0xff, 0x7f]);
+        assert_eq!(&output[0x1c..0x20], &[0, 0, 4, 0]);
+        // non-volatile and volatile sizes
+        assert_eq!(&output[0x34..0x3c], &[0, 0, 0, 0, 0x30, 0, 0, 0]);
+        assert_eq!(&output[0x3c..0x44], &[0, 0, 0, 0, 0x10, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_type17_nvdimm_size_values() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_nvdimm_non_volatile_size(NvdimmSizeValue::Bytes(gb(16)))
+            .unwrap();
+        m.set_nvdimm_volatile_size(NvdimmSizeValue::NotApplicable)
+            .unwrap();
+        m.set_nvdimm_cache_size(NvdimmSizeValue::Unknown).unwrap();
+        m.set_nvdimm_logical_size(NvdimmSizeValue::Unknown).unwrap();
+        m.serialize(&mut output);
+
+        assert_eq!(&output[0x34..0x3c], &[0, 0, 0, 0, 4, 0, 0, 0]);
+        assert_eq!(&output[0x3c..0x44], &[0; 8]);
+        assert_eq!(&output[0x44..0x4c], &[0xff; 8]);
+        assert_eq!(&output[0x4c..0x54], &[0xff; 8]);
+
+        assert_eq!(
+            m.get_nvdimm_non_volatile_size(),
+            NvdimmSizeValue::Bytes(gb(16))
+        );
+        assert_eq!(m.get_nvdimm_volatile_size(), NvdimmSizeValue::NotApplicable);
+        assert_eq!(m.get_nvdimm_cache_size(), NvdimmSizeValue::Unknown);
+        assert_eq!(m.get_nvdimm_logical_size(), NvdimmSizeValue::Unknown);
+    }
+
+    #[test]
+    fn test_type17_nvdimm_size_reserved() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_nvdimm_non_volatile_size(NvdimmSizeValue::Bytes(gb(16)))
+            .unwrap();
+        for bytes in [0, u64::MAX] {
+            assert_eq!(
+                m.set_nvdimm_non_volatile_size(NvdimmSizeValue::Bytes(bytes)),
+                Err(InvalidNvdimmSize(bytes))
+            );
+        }
+        m.serialize(&mut output);
+        assert_eq!(&output[0x34..0x3c], &[0, 0, 0, 0, 4, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_type17_hbm3() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_as_hbm3(4, 8);
+        m.serialize(&mut output);
+
+        assert_eq!(m.get_memory_size(), Some(gb(32)));
+        assert_eq!(&output[8..12], &[0, 4, 0, 4]);
+        // 32 GB doesn't fit in the size field, so it's in extended size (in MB)
+        assert_eq!(&output[0xc..0xe], &[0xff, 0x7f]);
+        assert_eq!(&output[0x1c..0x20], &[0, 0x80, 0, 0]);
+        assert_eq!(output[0xe], FormFactor::Die as u8);
+        assert_eq!(output[0xf], 4);
+        assert_eq!(output[0x12], MemoryType::Hbm3 as u8);
+        assert_eq!(output[0x28], MemoryTechnology::Dram as u8);
+        assert_eq!(&output[0x29..0x2b], &[0x8, 0]);
+
+        let mut m = MemoryDevice::new(0x21);
+        m.set_as_hbm2(8, 1);
+        assert_eq!(m.get_memory_size(), Some(gb(8)));
+    }
+
+    #[test]
+    fn test_jedec_manufacturer_id() {
+        // Samsung and Micron are both in bank 1, and Montage is in bank 7
+        let samsung = JedecManufacturerId::new_checked(1, 0xce).unwrap();
+        assert_eq!(samsung, JedecManufacturerId::from_spd_bytes(0x80, 0xce));
+        assert!(samsung.is_valid_parity());
+        let micron = JedecManufacturerId::new_checked(1, 0x2c).unwrap();
+        assert_eq!(u16::from(micron), 0x2c80);
+        let montage = JedecManufacturerId::new_checked(7, 0x32).unwrap();
+        assert_eq!(montage, JedecManufacturerId::from_spd_bytes(0x86, 0x32));
+
+        assert_eq!(
+            JedecManufacturerId::new_checked(1, 0x4e),
+            Err(JedecError::InvalidParity(0x4e))
+        );
+        assert_eq!(
+            JedecManufacturerId::new_checked(0, 0xce),
+            Err(JedecError::InvalidBank(0))
+        );
+        assert!(!JedecManufacturerId::from_spd_bytes(0x00, 0xce).is_valid_parity());
+        assert!(!JedecManufacturerId::UNKNOWN.is_valid_parity());
+
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_module_manufacturer(micron);
+        m.serialize(&mut output);
+        assert_eq!(&output[0x2c..0x2e], &[0x80, 0x2c]);
+    }
+
+    #[test]
+    fn test_type17_pmic_rcd() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_memory_type(MemoryType::Ddr5);
+        m.set_type_detail(TypeDetail::Registered.bits().into());
+        // Renesas PMIC and Montage RCD
+        m.set_pmic0_manufacturer(JedecManufacturerId::from_spd_bytes(0x80, 0xb3));
+        m.set_pmic0_revision(0x12);
+        m.set_rcd_manufacturer(JedecManufacturerId::from_spd_bytes(0x86, 0x32));
+        m.set_rcd_revision(0xb1);
+        m.serialize(&mut output);
+
+        // PMIC0 and RCD manufacturer and revision at offsets 0x5c to 0x64
+        assert_eq!(
+            &output[0x5c..0x64],
+            &[0x80, 0xb3, 0x12, 0, 0x86, 0x32, 0xb1, 0]
+        );
+    }
+
+    #[test]
+    fn test_type17_locators() {
+        let mut output = vec![];
+        let m = MemoryDevice::new_with_array(0x20, 0x10, "DIMM_A1", "BANK 0");
+        m.serialize(&mut output);
+
+        assert_eq!(m.data.device_locator, 1);
+        assert_eq!(m.data.bank_locator, 2);
+        assert_eq!(&output[4..6], &[0x10, 0]);
+        assert_eq!(&output[size_of::<Type17Data>()..], b"DIMM_A1\0BANK 0\0\0");
+    }
+
+    #[test]
+    fn test_type17_firmware_version() {
+        let mut m = MemoryDevice::new_with_array(0x20, 0x10, "DIMM_A1", "BANK 0");
+        m.set_firmware_version("1.0");
+        m.set_firmware_version("1.1");
+        m.set_part_number("PART");
+        assert_eq!(m.data.firmware_version, 3);
+        assert_eq!(m.data.part_number, 4);
+
+        m.clear_firmware_version();
+        assert_eq!(m.data.firmware_version, 0);
+        assert_eq!(m.data.part_number, 3);
+        // Clearing a string which isn't present does nothing
+        m.clear_firmware_version();
+
+        let mut output = vec![];
+        m.serialize(&mut output);
+        assert_eq!(
+            &output[size_of::<Type17Data>()..],
+            b"DIMM_A1\0BANK 0\0PART\0\0"
+        );
+
+        // DRAM has no firmware version, so no string is added
+        let mut output = vec![];
+        let m = MemoryDevice::new(0x21);
+        m.serialize(&mut output);
+        assert_eq!(output[0x2b], 0);
+        assert_eq!(output.len(), size_of::<Type17Data>() + 2);
+    }
+
+    #[test]
+    fn test_type17_spd_strings() {
+        assert_eq!(trim_smbios_string("abc \0 \0"), "abc");
+        assert_eq!(trim_smbios_string(" abc"), " abc");
+
+        let mut m = MemoryDevice::new(0x20);
+        m.set_module_part_number_bytes(b"M393A4K40DB3-CWE  ");
+        m.set_manufacturer_bytes(b"Samsung\0");
+        let mut output = vec![];
+        m.serialize(&mut output);
+        assert_eq!(
+            &output[size_of::<Type17Data>()..],
+            b"M393A4K40DB3-CWE\0Samsung\0\0"
+        );
+    }
+
+    #[test]
+    fn test_type17_empty_and_present() {
+        let mut output = vec![];
+        let m = MemoryDevice::new_empty_slot(0x20, 0x10, "DIMM_B1");
+        m.serialize(&mut output);
+        assert_eq!(
+            &output[4..0xe],
+            &[0x10, 0, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0, 0]
+        );
+        assert_eq!(output[0xe], FormFactor::Unknown as u8);
+        assert_eq!(output[0x12], MemoryType::Unknown as u8);
+        assert_eq!(m.get_memory_size(), Some(0));
+
+        let mut output = vec![];
+        let m = MemoryDevice::new_present(
+            0x21,
+            0x10,
+            gb(16),
+            MemoryType::Ddr5,
+            FormFactor::Dimm,
+            "DIMM_A1",
+            "BANK 0",
+            4800,
+        );
+        m.serialize(&mut output);
+        assert_eq!(&output[0xc..0xe], &[0x00, 0x40]);
+        assert_eq!(output[0xe], FormFactor::Dimm as u8);
+        assert_eq!(&output[0x15..0x17], &[0xc0, 0x12]);
+        assert_eq!(m.get_speed_mts(), 4800);
+        assert_eq!(m.get_device_locator(), "DIMM_A1");
+        assert_eq!(m.get_asset_tag(), "");
+
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x22);
+        m.set_speed_mts(70000);
+        m.serialize(&mut output);
+        assert_eq!(&output[0x15..0x17], &[0xff, 0xff]);
+        assert_eq!(&output[0x54..0x58], &70000u32.to_le_bytes());
+        assert_eq!(m.get_speed_mts(), 70000);
+    }
+
+    #[test]
+    fn test_type17_apply_spd_data() {
+        // A 16 GB dual rank DDR4-3200 RDIMM with ECC
+        let spd = SpdData {
+            memory_type: MemoryType::Ddr4,
+            size_mb: 16384,
+            speed_mts: 3200,
+            manufacturer_jedec: [0x80, 0xce],
+            part_number: *b"M393A2K43DB3-CWE  ",
+            serial_number: [0x12, 0x34, 0x56, 0x78],
+            form_factor: FormFactor::Dimm,
+            total_width_bits: 72,
+            data_width_bits: 64,
+            ranks: 2,
+        };
+
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x22);
+        m.apply_spd_data(&spd);
+        m.serialize(&mut output);
+        assert_eq!(&output[0x08..0x0c], &[72, 0, 64, 0]);
+        assert_eq!(&output[0x0c..0x0e], &[0x00, 0x40]);
+        assert_eq!(output[0x0e], FormFactor::Dimm as u8);
+        assert_eq!(output[0x12], MemoryType::Ddr4 as u8);
+        assert_eq!(&output[0x15..0x17], &[0x80, 0x0c]);
+        assert_eq!(&output[0x18..0x1b], &[1, 0, 2]);
+        assert_eq!(output[0x1b], 2);
+        assert_eq!(&output[0x2c..0x2e], &[0x80, 0xce]);
+        assert_eq!(&output[0x64..], b"12345678\0M393A2K43DB3-CWE\0\0");
+    }
+
+    #[test]
+    fn test_type17_configured_speed() {
+        for (speed_mts, speed, extended) in [
+            (0, [0, 0], 0u32),
+            (65534, [0xfe, 0xff], 0),
+            (65535, [0xff, 0xff], 65535),
+            (65536, [0xff, 0xff], 65536),
+        ] {
+            let mut output = vec![];
+            let mut m = MemoryDevice::new(0x22);
+            m.set_configured_speed_mts(speed_mts);
+            m.serialize(&mut output);
+            assert_eq!(&output[0x20..0x22], &speed);
+            assert_eq!(&output[0x58..0x5c], &extended.to_le_bytes());
+        }
+    }
+
+    #[test]
+    fn test_type17_voltage() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_memory_type(MemoryType::Ddr5);
+        m.set_minimum_voltage_mv(MEMORY_VOLTAGE_1050MV);
+        m.set_maximum_voltage_mv(MEMORY_VOLTAGE_1200MV);
+        m.set_configured_voltage_mv(MEMORY_VOLTAGE_1100MV);
+        m.serialize(&mut output);
+
+        // minimum, maximum and configured voltage at offsets 0x22, 0x24 and 0x26
+        assert_eq!(&output[0x22..0x28], &[0x1a, 0x4, 0xb0, 0x4, 0x4c, 0x4]);
+    }
+
+    #[test]
+    fn test_type17_product_ids() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_module_manufacturer(JedecManufacturerId::from_spd_bytes(0x80, 0x2c));
+        m.set_module_product_id_jedec(0x01, 0x52);
+        m.set_memory_subsystem_controller_manufacturer(JedecManufacturerId::UNKNOWN);
+        m.set_memory_subsystem_controller_product_id_jedec(0x00, 0x00);
+        m.serialize(&mut output);
+
+        // Manufacturer and product IDs at 0x2c and 0x2e, subsystem controller at 0x30 and 0x32
+        assert_eq!(&output[0x2c..0x34], &[0x80, 0x2c, 0x52, 0x01, 0, 0, 0, 0]);
+        assert_eq!(m.module_product_id_jedec(), (0x01, 0x52));
+
+        m.set_memory_subsystem_controller_product_id_jedec(0x12, 0x34);
+        let mut output = vec![];
+        m.serialize(&mut output);
+        assert_eq!(&output[0x32..0x34], &[0x34, 0x12]);
+    }
+
+    #[test]
+    fn test_type17_voltage_range() {
+        let mut output = vec![];
+        let mut m = MemoryDevice::new(0x20);
+        m.set_voltage_range(1050, 1200, 1100).unwrap();
+        m.serialize(&mut output);
+        assert_eq!(&output[0x22..0x28], &[0x1a, 0x4, 0xb0, 0x4, 0x4c, 0x4]);
+
+        // The existing voltages are left alone when the range is rejected
+        assert_eq!(
+            m.set_voltage_range(1100, 1200, 1000),
+            Err(SmbiosError::VoltageRangeInvalid {
+                min: 1100,
+                max: 1200,
+                configured: 1000
+            })
+        );
+        let mut rejected = vec![];
+        m.serialize(&mut rejected);
+        assert_eq!(output, rejected);
+
+        // Nothing is checked when any voltage is unknown
+        assert!(m.set_voltage_range(1200, 1100, 0).is_ok());
+    }
 }
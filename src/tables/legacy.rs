@@ -0,0 +1,393 @@
+// Copyright 2024 Rivos, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structures that are obsolete in current versions of the SMBIOS spec, but may still be needed
+//! to emulate older systems.
+
+use super::*;
+use bitflags::bitflags;
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum ErrorDetectingMethod {
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    None = 3,
+    Parity8Bit = 4,
+    Ecc32Bit = 5,
+    Ecc64Bit = 6,
+    Ecc128Bit = 7,
+    Crc = 8,
+}
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, Default)]
+    pub struct ErrorCorrectingCapability: u8 {
+        const Other = 1 << 0;
+        const Unknown = 1 << 1;
+        const None = 1 << 2;
+        const SingleBitErrorCorrecting = 1 << 3;
+        const DoubleBitErrorCorrecting = 1 << 4;
+        const ErrorScrubbing = 1 << 5;
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum InterleaveSupport {
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    OneWay = 3,
+    TwoWay = 4,
+    FourWay = 5,
+    EightWay = 6,
+    SixteenWay = 7,
+}
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, Default)]
+    pub struct MemorySpeeds: u16 {
+        const Other = 1 << 0;
+        const Unknown = 1 << 1;
+        const Speed70ns = 1 << 2;
+        const Speed60ns = 1 << 3;
+        const Speed50ns = 1 << 4;
+    }
+}
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, Default)]
+    pub struct MemoryTypes: u16 {
+        const Other = 1 << 0;
+        const Unknown = 1 << 1;
+        const Standard = 1 << 2;
+        const FastPageMode = 1 << 3;
+        const Edo = 1 << 4;
+        const Parity = 1 << 5;
+        const Ecc = 1 << 6;
+        const Simm = 1 << 7;
+        const Dimm = 1 << 8;
+        const BurstEdo = 1 << 9;
+        const Sdram = 1 << 10;
+    }
+}
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, Default)]
+    pub struct MemoryModuleVoltage: u8 {
+        const Voltage5V = 1 << 0;
+        const Voltage3_3V = 1 << 1;
+        const Voltage2_9V = 1 << 2;
+    }
+}
+
+// Type 5 SMBIOS table (Memory Controller Information)
+// The structure length depends on the number of associated memory module handles.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryControllerInformation {
+    handle: u16,
+    error_detecting_method: ErrorDetectingMethod,
+    error_correcting_capability: ErrorCorrectingCapability,
+    supported_interleave: InterleaveSupport,
+    current_interleave: InterleaveSupport,
+    maximum_memory_module_size: u8,
+    supported_speeds: MemorySpeeds,
+    supported_memory_types: MemoryTypes,
+    memory_module_voltage: MemoryModuleVoltage,
+    memory_module_handles: Vec<u16>,
+    enabled_error_correcting_capabilities: ErrorCorrectingCapability,
+}
+
+impl MemoryControllerInformation {
+    pub const TYPE_NUMBER: u8 = 5;
+
+    pub fn new(handle: u16) -> Self {
+        Self {
+            handle,
+            ..Default::default()
+        }
+    }
+
+    pub fn set_error_detecting_method(&mut self, method: ErrorDetectingMethod) {
+        self.error_detecting_method = method;
+    }
+
+    pub fn set_error_correcting_capability(&mut self, capability: ErrorCorrectingCapability) {
+        self.error_correcting_capability = capability;
+    }
+
+    pub fn set_supported_interleave(&mut self, interleave: InterleaveSupport) {
+        self.supported_interleave = interleave;
+    }
+
+    pub fn set_current_interleave(&mut self, interleave: InterleaveSupport) {
+        self.current_interleave = interleave;
+    }
+
+    // The maximum module size is 2^n MB
+    pub fn set_maximum_memory_module_size(&mut self, n: u8) {
+        self.maximum_memory_module_size = n;
+    }
+
+    pub fn set_supported_speeds(&mut self, speeds: MemorySpeeds) {
+        self.supported_speeds = speeds;
+    }
+
+    pub fn set_supported_memory_types(&mut self, types: MemoryTypes) {
+        self.supported_memory_types = types;
+    }
+
+    pub fn set_memory_module_voltage(&mut self, voltage: MemoryModuleVoltage) {
+        self.memory_module_voltage = voltage;
+    }
+
+    pub fn set_enabled_error_correcting_capabilities(
+        &mut self,
+        capability: ErrorCorrectingCapability,
+    ) {
+        self.enabled_error_correcting_capabilities = capability;
+    }
+
+    // Add the handle of a Type 6 structure for one of the controller's memory slots
+    pub fn add_memory_module_handle(&mut self, handle: u16) {
+        self.memory_module_handles.push(handle);
+    }
+}
+
+impl SmbiosStructure for MemoryControllerInformation {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        sink.byte(Self::TYPE_NUMBER);
+        sink.byte(
+            (0x10 + 2 * self.memory_module_handles.len())
+                .try_into()
+                .unwrap(),
+        );
+        sink.word(self.handle);
+        sink.byte(self.error_detecting_method as u8);
+        sink.byte(self.error_correcting_capability.bits());
+        sink.byte(self.supported_interleave as u8);
+        sink.byte(self.current_interleave as u8);
+        sink.byte(self.maximum_memory_module_size);
+        sink.word(self.supported_speeds.bits());
+        sink.word(self.supported_memory_types.bits());
+        sink.byte(self.memory_module_voltage.bits());
+        sink.byte(self.memory_module_handles.len().try_into().unwrap());
+        for handle in &self.memory_module_handles {
+            sink.word(*handle);
+        }
+        sink.byte(self.enabled_error_correcting_capabilities.bits());
+        sink.byte(0);
+        sink.byte(0);
+    }
+
+    fn byte_length(&self) -> usize {
+        0x10 + 2 * self.memory_module_handles.len() + 2
+    }
+}
+
+impl SmbiosTable for MemoryControllerInformation {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
+    }
+
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
+        for handle in &mut self.memory_module_handles {
+            *handle = remap(*handle);
+        }
+    }
+}
+
+// Type 6 SMBIOS table (Memory Module Information)
+simple_smbios_structure! {
+    6,
+    struct MemoryModuleInformation {
+        data: struct Type6Data {
+            socket_designation: StringIndex,
+            bank_connections: u8,
+            current_speed: u8,
+            current_memory_type: U16,
+            installed_size: u8,
+            enabled_size: u8,
+            error_status: u8,
+        }
+    }
+}
+static_assertions::const_assert!(size_of::<Type6Data>() == 0xc);
+
+impl MemoryModuleInformation {
+    pub fn set_current_memory_type_flags(&mut self, types: MemoryTypes) {
+        self.data.current_memory_type = types.bits().into();
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum OnBoardDeviceType {
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    Video = 3,
+    ScsiController = 4,
+    Ethernet = 5,
+    TokenRing = 6,
+    Sound = 7,
+    PataController = 8,
+    SataController = 9,
+    SasController = 10,
+}
+
+#[derive(Clone, Debug)]
+pub struct OnBoardDevice {
+    device_type: OnBoardDeviceType,
+    enabled: bool,
+    description: String,
+}
+
+// Type 10 SMBIOS table (On Board Devices Information)
+// Each device is a (type, description string) pair, so the structure length depends on the
+// number of devices.
+#[derive(Clone, Debug, Default)]
+pub struct OnBoardDevicesInformation {
+    handle: u16,
+    devices: Vec<OnBoardDevice>,
+}
+
+impl OnBoardDevicesInformation {
+    pub const TYPE_NUMBER: u8 = 10;
+
+    pub fn new(handle: u16) -> Self {
+        Self {
+            handle,
+            devices: Vec::new(),
+        }
+    }
+
+    pub fn add_device(&mut self, ty: OnBoardDeviceType, enabled: bool, description: &str) {
+        self.devices.push(OnBoardDevice {
+            device_type: ty,
+            enabled,
+            description: description.into(),
+        });
+    }
+}
+
+impl SmbiosStructure for OnBoardDevicesInformation {
+    fn serialize(&self, sink: &mut dyn Sink) {
+        sink.byte(Self::TYPE_NUMBER);
+        sink.byte((4 + 2 * self.devices.len()).try_into().unwrap());
+        sink.word(self.handle);
+        for (i, device) in self.devices.iter().enumerate() {
+            // Bit 7 is the device status, bits 6:0 are the device type
+            sink.byte(device.device_type as u8 | (u8::from(device.enabled) << 7));
+            sink.byte((i + 1).try_into().unwrap());
+        }
+        for device in &self.devices {
+            sink.vec(device.description.as_bytes());
+            sink.byte(0);
+        }
+        sink.byte(0);
+        if self.devices.is_empty() {
+            sink.byte(0);
+        }
+    }
+
+    fn byte_length(&self) -> usize {
+        let strings: usize = self.devices.iter().map(|d| d.description.len() + 1).sum();
+        4 + 2 * self.devices.len() + strings + if self.devices.is_empty() { 2 } else { 1 }
+    }
+}
+
+impl SmbiosTable for OnBoardDevicesInformation {
+    fn structure_type(&self) -> u8 {
+        Self::TYPE_NUMBER
+    }
+
+    fn handle(&self) -> u16 {
+        self.handle
+    }
+
+    fn clone_box(&self) -> alloc::boxed::Box<dyn SmbiosTable> {
+        alloc::boxed::Box::new(self.clone())
+    }
+
+    fn remap_handles(&mut self, remap: &dyn Fn(u16) -> u16) {
+        self.handle = remap(self.handle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_type5() {
+        let expected = [
+            5u8, 0x14, 0x50, 0, 6, 0x8, 4, 3, 9, 0x8, 0, 0x40, 1, 2, 2, 0x60, 0, 0x61, 0, 0x8, 0, 0,
+        ];
+
+        let mut output = vec![];
+        let mut m = MemoryControllerInformation::new(0x50);
+        m.set_error_detecting_method(ErrorDetectingMethod::Ecc64Bit);
+        m.set_error_correcting_capability(ErrorCorrectingCapability::SingleBitErrorCorrecting);
+        m.set_supported_interleave(InterleaveSupport::TwoWay);
+        m.set_current_interleave(InterleaveSupport::OneWay);
+        m.set_maximum_memory_module_size(9);
+        m.set_supported_speeds(MemorySpeeds::Speed60ns);
+        m.set_supported_memory_types(MemoryTypes::Ecc | MemoryTypes::Dimm);
+        m.set_memory_module_voltage(MemoryModuleVoltage::Voltage3_3V);
+        m.add_memory_module_handle(0x60);
+        m.add_memory_module_handle(0x61);
+        m.set_enabled_error_correcting_capabilities(
+            ErrorCorrectingCapability::SingleBitErrorCorrecting,
+        );
+        m.serialize(&mut output);
+
+        assert_eq!(expected.as_slice(), output);
+    }
+
+    #[test]
+    fn test_type6() {
+        let expected = [
+            6u8, 0xc, 0x60, 0, 1, 0x01, 60, 0x10, 1, 7, 7, 0, b'D', b'I', b'M', b'M', b'0', 0, 0,
+        ];
+
+        let mut output = vec![];
+        let mut m = MemoryModuleInformation::new(0x60);
+        m.set_socket_designation("DIMM0");
+        m.set_bank_connections(0x01);
+        m.set_current_speed(60);
+        m.set_current_memory_type_flags(MemoryTypes::Edo | MemoryTypes::Dimm);
+        m.set_installed_size(7);
+        m.set_enabled_size(7);
+        m.serialize(&mut output);
+
+        assert_eq!(expected.as_slice(), output);
+    }
+
+    #[test]
+    fn test_type10() {
+        let expected = [
+            10u8, 8, 0x10, 0, 0x83, 1, 0x05, 2, b'V', b'G', b'A', 0, b'N', b'I', b'C', 0, 0,
+        ];
+
+        let mut output = vec![];
+        let mut o = OnBoardDevicesInformation::new(0x10);
+        o.add_device(OnBoardDeviceType::Video, true, "VGA");
+        o.add_device(OnBoardDeviceType::Ethernet, false, "NIC");
+        o.serialize(&mut output);
+
+        assert_eq!(expected.as_slice(), output);
+    }
+}
@@ -0,0 +1,272 @@
+// Copyright 2024 Rivos, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Randomized tests that generate many instances of each structure and check the invariants
+//! of the serialized output, to catch regressions in the macro-generated code.
+
+use super::*;
+use crate::parser::SimpleStructureIterator;
+use alloc::vec;
+
+const ITERATIONS: usize = 256;
+
+// A small xorshift generator, so the tests are reproducible and need no extra dependencies
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn byte(&mut self) -> u8 {
+        self.next() as u8
+    }
+
+    fn bool(&mut self) -> bool {
+        self.next() & 1 == 1
+    }
+
+    fn string(&mut self) -> String {
+        let len = 1 + self.next() % 16;
+        (0..len)
+            .map(|_| (b' ' + self.byte() % 95) as char)
+            .collect()
+    }
+}
+
+// Randomly set each of the listed string fields, returning the number that were set
+macro_rules! random_strings {
+    ($rng:ident, $s:ident, $($setter:ident),*) => {{
+        let mut n = 0;
+        $(
+            if $rng.bool() {
+                $s.$setter(&$rng.string());
+                n += 1;
+            }
+        )*
+        n
+    }};
+}
+
+fn check(ty: u8, fixed_len: usize, num_strings: usize, s: &dyn SmbiosStructure) {
+    let mut output = vec![];
+    s.serialize(&mut output);
+
+    assert!(output.len() >= 4);
+    assert_eq!(s.byte_length(), output.len());
+    assert_eq!(output[0], ty);
+    assert_eq!(usize::from(output[1]), fixed_len);
+    assert!(output.ends_with(&[0, 0]));
+    if num_strings == 0 {
+        assert_eq!(output.len(), fixed_len + 2);
+    } else {
+        // Each string is terminated, as is the string section
+        assert_eq!(
+            output[fixed_len..].iter().filter(|b| **b == 0).count(),
+            num_strings + 1
+        );
+    }
+
+    let mut it = SimpleStructureIterator::new(&output);
+    let header = it.next().unwrap().unwrap();
+    assert_eq!(header.ty, ty);
+    assert_eq!(usize::from(header.length), fixed_len);
+    assert_eq!(header.raw().len(), output.len());
+    assert!(it.next().is_none());
+}
+
+#[test]
+fn test_random_structures() {
+    let mut rng = Rng(0x5eed_1234_abcd_9876);
+    for _ in 0..ITERATIONS {
+        let handle = rng.next() as u16;
+
+        let mut b = BiosInformation::new(handle);
+        let n = random_strings!(rng, b, set_vendor, set_bios_version, set_bios_release_date);
+        b.set_bios_rom_size(rng.byte());
+        check(0, size_of::<Type0Data>(), n, &b);
+
+        let mut s = SystemInformation::new(handle);
+        let n = random_strings!(
+            rng,
+            s,
+            set_manufacturer,
+            set_product_name,
+            set_version,
+            set_serial_number,
+            set_sku_number,
+            set_family
+        );
+        s.set_wakeup_type_from_u8(rng.byte());
+        check(1, size_of::<Type1Data>(), n, &s);
+
+        let mut e = SystemEnclosure::new(handle);
+        let n = random_strings!(
+            rng,
+            e,
+            set_manufacturer,
+            set_version,
+            set_serial_number,
+            set_asset_tag,
+            set_sku_number
+        );
+        let elements = rng.next() % 16;
+        for _ in 0..elements {
+            let ty = ContainedElementType::baseboard_type(rng.byte() & 0x7f).unwrap();
+            e.add_contained_element(ty, 0, 1 + rng.byte() % 255)
+                .unwrap();
+        }
+        check(3, 0x16 + 3 * elements as usize, n, &e);
+
+        let mut p = ProcessorInformation::new(handle);
+        let n = random_strings!(
+            rng,
+            p,
+            set_socket_designation,
+            set_processor_manufacturer,
+            set_processor_version,
+            set_serial_number,
+            set_asset_tag,
+            set_part_number
+        );
+        p.set_processor_id(rng.next().into());
+        p.set_core_count(rng.byte());
+        check(4, size_of::<Type4Data>(), n, &p);
+
+        let mut c = CacheInformation::new(handle);
+        let n = random_strings!(rng, c, set_socket_designation);
+        c.set_installed_cache_size2((rng.next() as u32).into());
+        check(7, size_of::<Type7Data>(), n, &c);
+
+        let mut s = SystemSlots::new(handle);
+        let n = random_strings!(rng, s, set_slot_designation);
+        s.set_bus_number(rng.byte());
+        check(9, size_of::<Type9Data>(), n, &s);
+
+        let mut g = GroupAssociations::new(handle);
+        let n = random_strings!(rng, g, set_group_name);
+        let items = rng.next() % 16;
+        for _ in 0..items {
+            g.add_item(GroupAssociationItem::new(rng.byte(), rng.next() as u16))
+                .unwrap();
+        }
+        check(14, size_of::<Type14Data>() + 3 * items as usize, n, &g);
+
+        let mut l = SystemEventLog::new_memory_mapped(handle, rng.next() as u32);
+        let descriptors = rng.next() % 32;
+        for _ in 0..descriptors {
+            l.add_event_type_descriptor(rng.byte(), rng.byte()).unwrap();
+        }
+        check(15, 0x17 + 2 * descriptors as usize, 0, &l);
+
+        let mut b = PortableBattery::new(handle);
+        let n = random_strings!(
+            rng,
+            b,
+            set_location,
+            set_manufacturer,
+            set_manufacture_date,
+            set_serial_number,
+            set_device_name,
+            set_sbds_version_number,
+            set_sbds_device_chemistry
+        );
+        b.set_design_capacity_mwh(rng.next() as u16 as u32 * 100)
+            .unwrap();
+        check(22, size_of::<Type22Data>(), n, &b);
+
+        let mut v = VoltageProbe::new(handle);
+        let n = random_strings!(rng, v, set_description);
+        v.set_maximum_value(ProbeReading::from_value(rng.next() as i16));
+        check(26, size_of::<Type26Data>(), n, &v);
+
+        let mut t = TemperatureProbe::new(handle);
+        let n = random_strings!(rng, t, set_description);
+        t.set_minimum_value(ProbeReading::from_value(rng.next() as i16));
+        check(28, size_of::<Type28Data>(), n, &t);
+
+        let mut c = CurrentProbe::new(handle);
+        let n = random_strings!(rng, c, set_description);
+        c.set_nominal_value(ProbeReading::from_value(rng.next() as i16));
+        check(29, size_of::<Type29Data>(), n, &c);
+
+        let mut a = PhysicalMemoryArray::new(handle);
+        a.set_memory_capacity(rng.next() >> 8);
+        check(16, size_of::<Type16Data>(), 0, &a);
+
+        let mut m = MemoryDevice::new(handle);
+        let n = random_strings!(
+            rng,
+            m,
+            set_device_locator,
+            set_bank_locator,
+            set_manufacturer,
+            set_serial_number,
+            set_asset_tag,
+            set_part_number,
+            set_firmware_version
+        );
+        m.set_memory_size(Some(rng.next() >> 24));
+        check(17, size_of::<Type17Data>(), n, &m);
+
+        let mut a = MemoryArrayMappedAddress::new(handle);
+        a.set_address_range(rng.next(), rng.next());
+        check(19, size_of::<Type19Data>(), 0, &a);
+
+        let mut d = MemoryDeviceMappedAddress::new(handle);
+        d.set_address_range(rng.next(), rng.next());
+        check(20, size_of::<Type20Data>(), 0, &d);
+
+        let mut t = TpmDevice::new(handle);
+        let n = random_strings!(rng, t, set_description);
+        t.set_oem_defined((rng.next() as u32).into());
+        check(43, size_of::<Type43Data>(), n, &t);
+
+        let mut r = RiscvProcessorAdditionalInformation::new(handle);
+        r.set_hart_id(u128::from(rng.next()).into());
+        check(44, size_of::<RiscvType44Data>(), 0, &r);
+
+        let mut l = LoongArchProcessorAdditionalInformation::new(handle);
+        l.set_core_id(rng.next().into());
+        check(44, size_of::<LoongArchType44Data>(), 0, &l);
+
+        check(127, 4, 0, &EndOfTable::new(handle));
+
+        let mut c = MemoryChannel::new(handle);
+        let devices = rng.next() % 16;
+        for _ in 0..devices {
+            c.add_device(rng.byte(), rng.next() as u16).unwrap();
+        }
+        check(37, 7 + 3 * devices as usize, 0, &c);
+
+        let mut a = AdditionalInformation::new(handle);
+        let entries = rng.next() % 8;
+        let mut len = 5;
+        for _ in 0..entries {
+            let value: Vec<u8> = (0..1 + rng.next() % 8).map(|_| rng.byte()).collect();
+            len += 5 + value.len();
+            a.add_entry(rng.next() as u16, rng.byte(), &value, &rng.string())
+                .unwrap();
+        }
+        check(40, len, entries as usize, &a);
+
+        let mut f = FirmwareInventoryInformation::new(handle);
+        let n = random_strings!(
+            rng,
+            f,
+            set_component_name,
+            set_release_date,
+            set_manufacturer,
+            set_lowest_supported_version
+        );
+        let components = rng.next() % 16;
+        for _ in 0..components {
+            f.add_associated_component(rng.next() as u16).unwrap();
+        }
+        f.set_image_size(Some(rng.next()));
+        check(45, 0x18 + 2 * components as usize, n, &f);
+    }
+}
@@ -1,9 +1,13 @@
 // Copyright 2024 Rivos, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use alloc::vec::Vec;
 use bitfield::bitfield;
 use bitflags::bitflags;
-use zerocopy::AsBytes;
+use zerocopy::{byteorder, byteorder::LE, AsBytes};
+
+mod probe;
+pub use probe::*;
 
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, AsBytes)]
@@ -30,6 +34,18 @@ bitflags! {
         const SocketedRom = 1 << 17;
         const BootFromPcCardSupported = 1 << 18;
         const EddSpecSupported = 1 << 19;
+        const Int13hJapaneseFloppyNec9800 = 1 << 20;
+        const Int13hJapaneseFloppyToshiba = 1 << 21;
+        const Int13hFloppy525In360Kb = 1 << 22;
+        const Int13hFloppy525In1_2Mb = 1 << 23;
+        const Int13hFloppy35In720Kb = 1 << 24;
+        const Int13hFloppy35In2_88Mb = 1 << 25;
+        const Int5hPrintScreenSupported = 1 << 26;
+        const Int9h8042KeyboardSupported = 1 << 27;
+        const Int14hSerialSupported = 1 << 28;
+        const Int17hPrinterSupported = 1 << 29;
+        const Int10hCgaMonoVideoSupported = 1 << 30;
+        const NecPc98 = 1 << 31;
     }
 }
 
@@ -41,6 +57,10 @@ bitflags! {
         const AcpiSupported = 1 << 0;
         const UsbLegacySupported = 1 << 1;
         const AgpSupported = 1 << 2;
+        const I2oBootSupported = 1 << 3;
+        const Ls120BootSupported = 1 << 4;
+        const AtapiZipBootSupported = 1 << 5;
+        const Boot1394Supported = 1 << 6;
         const SmartBatterySupported = 1 << 7;
     }
 }
@@ -60,19 +80,171 @@ bitflags! {
     }
 }
 
-#[repr(u8)]
-#[derive(Copy, Clone, Debug, Default, AsBytes)]
+// The spec-defined "Other" wakeup type is `Other(1)`; any value the spec doesn't define is
+// also carried in `Other` so that it can be round-tripped.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum WakeupType {
-    Reserved = 0,
+    Reserved,
+    Other(u8),
+    #[default]
+    Unknown,
+    ApmTimer,
+    ModemRing,
+    LanRemote,
+    PowerSwitch,
+    PciPme,
+    AcPowerRestored,
+}
+
+impl WakeupType {
+    pub fn from_u8(v: u8) -> WakeupType {
+        match v {
+            0 => WakeupType::Reserved,
+            2 => WakeupType::Unknown,
+            3 => WakeupType::ApmTimer,
+            4 => WakeupType::ModemRing,
+            5 => WakeupType::LanRemote,
+            6 => WakeupType::PowerSwitch,
+            7 => WakeupType::PciPme,
+            8 => WakeupType::AcPowerRestored,
+            v => WakeupType::Other(v),
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        match self {
+            WakeupType::Reserved => 0,
+            WakeupType::Other(v) => v,
+            WakeupType::Unknown => 2,
+            WakeupType::ApmTimer => 3,
+            WakeupType::ModemRing => 4,
+            WakeupType::LanRemote => 5,
+            WakeupType::PowerSwitch => 6,
+            WakeupType::PciPme => 7,
+            WakeupType::AcPowerRestored => 8,
+        }
+    }
+}
+
+// The encoded form of `WakeupType`, as stored in the Type 1 structure
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AsBytes)]
+pub struct WakeupTypeRaw(u8);
+
+impl Default for WakeupTypeRaw {
+    fn default() -> Self {
+        WakeupType::default().into()
+    }
+}
+
+impl From<WakeupType> for WakeupTypeRaw {
+    fn from(w: WakeupType) -> Self {
+        WakeupTypeRaw(w.to_u8())
+    }
+}
+
+impl From<WakeupTypeRaw> for WakeupType {
+    fn from(w: WakeupTypeRaw) -> Self {
+        WakeupType::from_u8(w.0)
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum ChassisType {
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    Desktop = 3,
+    LowProfileDesktop = 4,
+    PizzaBox = 5,
+    MiniTower = 6,
+    Tower = 7,
+    Portable = 8,
+    Laptop = 9,
+    Notebook = 0xa,
+    HandHeld = 0xb,
+    DockingStation = 0xc,
+    AllInOne = 0xd,
+    SubNotebook = 0xe,
+    SpaceSaving = 0xf,
+    LunchBox = 0x10,
+    MainServerChassis = 0x11,
+    ExpansionChassis = 0x12,
+    SubChassis = 0x13,
+    BusExpansionChassis = 0x14,
+    PeripheralChassis = 0x15,
+    RaidChassis = 0x16,
+    RackMountChassis = 0x17,
+    SealedCasePc = 0x18,
+    MultiSystemChassis = 0x19,
+    CompactPci = 0x1a,
+    AdvancedTca = 0x1b,
+    Blade = 0x1c,
+    BladeEnclosure = 0x1d,
+    Tablet = 0x1e,
+    Convertible = 0x1f,
+    Detachable = 0x20,
+    IotGateway = 0x21,
+    EmbeddedPc = 0x22,
+    MiniPc = 0x23,
+    StickPc = 0x24,
+}
+
+// Boot-up, power supply and thermal state of an enclosure
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum ChassisState {
     Other = 1,
     #[default]
     Unknown = 2,
-    ApmTimer = 3,
-    ModemRing = 4,
-    LanRemote = 5,
-    PowerSwitch = 6,
-    PciPme = 7,
-    AcPowerRestored = 8,
+    Safe = 3,
+    Warning = 4,
+    Critical = 5,
+    NonRecoverable = 6,
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum ChassisSecurityStatus {
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    None = 3,
+    ExternalInterfaceLockedOut = 4,
+    ExternalInterfaceEnabled = 5,
+}
+
+// The type of an element contained in an enclosure. Bit 7 selects between an SMBIOS structure
+// type (set) and a baseboard type (clear), with the type itself in bits 6:0.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ContainedElementType(u8);
+
+// A value which doesn't fit in the range allowed for it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RangeError(pub u8);
+
+impl ContainedElementType {
+    pub fn smbios_type(ty: u8) -> Result<Self, RangeError> {
+        match ty {
+            0..=0x7e => Ok(Self(0x80 | ty)),
+            ty => Err(RangeError(ty)),
+        }
+    }
+
+    // Baseboard types are the Type 2 board type values, which must fit in bits 6:0
+    pub fn baseboard_type(ty: u8) -> Result<Self, RangeError> {
+        match ty {
+            0..=0x7f => Ok(Self(ty)),
+            ty => Err(RangeError(ty)),
+        }
+    }
+}
+
+impl From<ContainedElementType> for u8 {
+    fn from(t: ContainedElementType) -> Self {
+        t.0
+    }
 }
 
 #[repr(u8)]
@@ -99,25 +271,199 @@ pub enum ProcessorFamily {
 #[repr(u16)]
 #[derive(Copy, Clone, Debug, Default, AsBytes)]
 pub enum ProcessorFamily2 {
+    ArmV7 = 0x100,
+    ArmV8 = 0x101,
+    ArmV9 = 0x102,
+    Sh3 = 0x104,
+    Sh4 = 0x105,
+    Arm = 0x118,
+    StrongArm = 0x119,
+    Cyrix6x86 = 0x12c,
+    MediaGx = 0x12d,
+    Mii = 0x12e,
+    WinChip = 0x140,
+    Dsp = 0x15e,
+    VideoProcessor = 0x1f4,
     RiscvRv32 = 0x200,
     RiscvRv64 = 0x201,
     RiscvRv128 = 0x202,
+    LoongArch = 0x258,
+    Loongson1 = 0x259,
+    Loongson2 = 0x25a,
+    Loongson3 = 0x25b,
+    Loongson2K = 0x25c,
+    Loongson3A = 0x25d,
+    Loongson3B = 0x25e,
+    Loongson3C = 0x25f,
+    Loongson3D = 0x260,
+    Loongson3E = 0x261,
+    DualCoreLoongson2K2xxx = 0x262,
     #[default]
     Reserved = 0xfffe,
 }
 
+// The encoded form of `ProcessorFamily`, as stored in the Type 4 structure. Any value can be
+// held here, including ones this crate doesn't have a name for.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AsBytes)]
+pub struct ProcessorFamilyRaw(pub u8);
+
+impl Default for ProcessorFamilyRaw {
+    fn default() -> Self {
+        ProcessorFamily::default().into()
+    }
+}
+
+impl From<ProcessorFamily> for ProcessorFamilyRaw {
+    fn from(f: ProcessorFamily) -> Self {
+        ProcessorFamilyRaw(f as u8)
+    }
+}
+
+// The encoded form of `ProcessorFamily2`, as stored in the Type 4 structure
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AsBytes)]
+pub struct ProcessorFamily2Raw(pub byteorder::U16<LE>);
+
+impl Default for ProcessorFamily2Raw {
+    fn default() -> Self {
+        ProcessorFamily2::default().into()
+    }
+}
+
+impl From<ProcessorFamily2> for ProcessorFamily2Raw {
+    fn from(f: ProcessorFamily2) -> Self {
+        ProcessorFamily2Raw((f as u16).into())
+    }
+}
+
+// A processor family in either encoding. Families that fit in a byte are stored directly in the
+// Type 4 `processor_family` field, others are stored in `processor_family2` with
+// `processor_family` set to `ProcessorFamily::ObtainFrom2`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ProcessorFamilyValue {
+    Byte(u8),
+    Word(u16),
+}
+
+impl From<ProcessorFamily> for ProcessorFamilyValue {
+    fn from(f: ProcessorFamily) -> Self {
+        ProcessorFamilyValue::Byte(f as u8)
+    }
+}
+
+impl From<ProcessorFamily2> for ProcessorFamilyValue {
+    fn from(f: ProcessorFamily2) -> Self {
+        ProcessorFamilyValue::Word(f as u16)
+    }
+}
+
+// Bits 2:0 of the Type 4 status field
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CpuStatus {
+    #[default]
+    Unknown = 0,
+    Enabled = 1,
+    DisabledByUser = 2,
+    DisabledByBios = 3,
+    Idle = 4,
+    Other = 7,
+}
+
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, Default, AsBytes)]
 pub enum ProcessorUpgrade {
-    Other = 1,
+    Other = 0x01,
     #[default]
-    Unknown = 2,
-    DaughterBoard = 3,
-    None = 6,
+    Unknown = 0x02,
+    DaughterBoard = 0x03,
+    ZifSocket = 0x04,
+    ReplaceablePiggyBack = 0x05,
+    None = 0x06,
+    LifSocket = 0x07,
+    Slot1 = 0x08,
+    Slot2 = 0x09,
+    Socket370Pin = 0x0a,
+    SlotA = 0x0b,
+    SlotM = 0x0c,
+    Socket423 = 0x0d,
+    SocketA = 0x0e, // 462 pins
+    Socket478 = 0x0f,
+    Socket754 = 0x10,
+    Socket940 = 0x11,
+    Socket939 = 0x12,
+    SocketMpga604 = 0x13,
+    SocketLga771 = 0x14,
+    SocketLga775 = 0x15,
+    SocketS1 = 0x16,
+    SocketAm2 = 0x17,
+    SocketF = 0x18, // 1207 pins
+    SocketLga1366 = 0x19,
+    SocketG34 = 0x1a,
+    SocketAm3 = 0x1b,
+    SocketC32 = 0x1c,
+    SocketLga1156 = 0x1d,
+    SocketLga1567 = 0x1e,
+    SocketPga988a = 0x1f,
+    SocketBga1288 = 0x20,
+    SocketRpga988b = 0x21,
+    SocketBga1023 = 0x22,
+    SocketBga1224 = 0x23,
+    SocketLga1155 = 0x24,
+    SocketLga1356 = 0x25,
+    SocketLga2011 = 0x26,
+    SocketFs1 = 0x27,
+    SocketFs2 = 0x28,
+    SocketFm1 = 0x29,
+    SocketFm2 = 0x2a,
+    SocketLga2011_3 = 0x2b,
+    SocketLga1356_3 = 0x2c,
+    SocketLga1150 = 0x2d,
+    SocketBga1168 = 0x2e,
+    SocketBga1234 = 0x2f,
+    SocketBga1364 = 0x30,
+    SocketAm4 = 0x31,
+    SocketLga1151 = 0x32,
+    SocketBga1356 = 0x33,
+    SocketBga1440 = 0x34,
+    SocketBga1515 = 0x35,
+    SocketLga3647_1 = 0x36,
+    SocketSp3 = 0x37,
+    SocketSp3r2 = 0x38,
+    SocketLga2066 = 0x39,
+    SocketBga1392 = 0x3a,
+    SocketBga1510 = 0x3b,
+    SocketBga1528 = 0x3c,
+    SocketLga4189 = 0x3d,
+    SocketLga1200 = 0x3e,
+    SocketLga4677 = 0x3f,
+    SocketLga1700 = 0x40,
+    SocketBga1744 = 0x41,
+    SocketBga1781 = 0x42,
+    SocketBga1211 = 0x43,
+    SocketBga2422 = 0x44,
+    SocketLga1211 = 0x45,
+    SocketLga2422 = 0x46,
+    SocketLga5773 = 0x47,
+    SocketBga5773 = 0x48,
+    SocketAm5 = 0x49,
+    SocketSp5 = 0x4a,
+    SocketSp6 = 0x4b,
+    SocketBga883 = 0x4c,
+    SocketBga1190 = 0x4d,
+    SocketBga4129 = 0x4e,
+    SocketLga4710 = 0x4f,
+    SocketLga7529 = 0x50,
 }
 
+// The Type 4 processor characteristics
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, AsBytes)]
+pub struct RiscvProcessorCharacteristics1(u16);
+
 bitflags! {
-    pub struct RiscvProcessorCharacteristics1: u16 {
+    impl RiscvProcessorCharacteristics1: u16 {
         const Reserved = 1 << 0;
         const Unknown = 1 << 1;
         const Bits64Capable = 1 << 2;
@@ -154,8 +500,10 @@ pub enum SramType {
     Asynchronous = 1 << 6,
 }
 
+// Error correction used by a cache (Type 7). This has the same values as
+// `ErrorCorrectionType`, which is used by memory arrays (Type 16).
 #[repr(u8)]
-#[derive(Copy, Clone, Debug, Default, AsBytes)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AsBytes)]
 pub enum EccType {
     Other = 1,
     #[default]
@@ -164,6 +512,33 @@ pub enum EccType {
     Parity = 4,
     SingleBitEcc = 5,
     MultiBitEcc = 6,
+    Crc = 7,
+}
+
+impl EccType {
+    pub fn is_error_correcting(&self) -> bool {
+        matches!(self, EccType::SingleBitEcc | EccType::MultiBitEcc)
+    }
+}
+
+impl From<EccType> for ErrorCorrectionType {
+    fn from(e: EccType) -> Self {
+        match e {
+            EccType::Other => ErrorCorrectionType::Other,
+            EccType::Unknown => ErrorCorrectionType::Unknown,
+            EccType::None => ErrorCorrectionType::None,
+            EccType::Parity => ErrorCorrectionType::Parity,
+            EccType::SingleBitEcc => ErrorCorrectionType::SingleBitEcc,
+            EccType::MultiBitEcc => ErrorCorrectionType::MultiBitEcc,
+            EccType::Crc => ErrorCorrectionType::Crc,
+        }
+    }
+}
+
+impl PartialEq<ErrorCorrectionType> for EccType {
+    fn eq(&self, other: &ErrorCorrectionType) -> bool {
+        ErrorCorrectionType::from(*self) == *other
+    }
 }
 
 #[repr(u8)]
@@ -197,19 +572,157 @@ pub enum Associativity {
     SetAssociative20Way = 14,
 }
 
+impl Associativity {
+    pub fn from_u8(v: u8) -> Option<Associativity> {
+        use Associativity::*;
+        Some(match v {
+            1 => Other,
+            2 => Unknown,
+            3 => DirectMapped,
+            4 => SetAssociative2Way,
+            5 => SetAssociative4Way,
+            6 => FullyAssociative,
+            7 => SetAssociative8Way,
+            8 => SetAssociative16Way,
+            9 => SetAssociative12Way,
+            10 => SetAssociative24Way,
+            11 => SetAssociative32Way,
+            12 => SetAssociative48Way,
+            13 => SetAssociative64Way,
+            14 => SetAssociative20Way,
+            _ => return None,
+        })
+    }
+
+    // A direct-mapped cache is 1-way; fully associative caches have no fixed number of ways
+    pub fn from_ways(n: u32) -> Option<Associativity> {
+        use Associativity::*;
+        Some(match n {
+            1 => DirectMapped,
+            2 => SetAssociative2Way,
+            4 => SetAssociative4Way,
+            8 => SetAssociative8Way,
+            12 => SetAssociative12Way,
+            16 => SetAssociative16Way,
+            20 => SetAssociative20Way,
+            24 => SetAssociative24Way,
+            32 => SetAssociative32Way,
+            48 => SetAssociative48Way,
+            64 => SetAssociative64Way,
+            _ => return None,
+        })
+    }
+
+    pub fn ways(&self) -> Option<u32> {
+        use Associativity::*;
+        Some(match self {
+            DirectMapped => 1,
+            SetAssociative2Way => 2,
+            SetAssociative4Way => 4,
+            SetAssociative8Way => 8,
+            SetAssociative12Way => 12,
+            SetAssociative16Way => 16,
+            SetAssociative20Way => 20,
+            SetAssociative24Way => 24,
+            SetAssociative32Way => 32,
+            SetAssociative48Way => 48,
+            SetAssociative64Way => 64,
+            Other | Unknown | FullyAssociative => return None,
+        })
+    }
+}
+
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, Default, AsBytes)]
 pub enum SlotType {
     Other = 1,
     #[default]
     Unknown = 2,
+    Isa = 3,
+    Mca = 4,
+    Eisa = 5,
+    Pci = 6,
+    PcCard = 7,
+    VlVesa = 8,
+    Proprietary = 9,
+    ProcessorCard = 0xa,
+    ProprietaryMemoryCard = 0xb,
+    IoRiserCard = 0xc,
+    NuBus = 0xd,
+    Pci66MHz = 0xe,
+    Agp = 0xf,
+    Agp2x = 0x10,
+    Agp4x = 0x11,
+    PciX = 0x12,
+    Agp8x = 0x13,
+    M2Socket1Dp = 0x14,
+    M2Socket1Sd = 0x15,
+    M2Socket2 = 0x16,
+    M2Socket3 = 0x17,
+    MxmTypeI = 0x18,
+    MxmTypeII = 0x19,
+    MxmTypeIIIStandard = 0x1a,
+    MxmTypeIIIHe = 0x1b,
+    MxmTypeIV = 0x1c,
+    Mxm3TypeA = 0x1d,
+    Mxm3TypeB = 0x1e,
+    PcieGen2Sff8639 = 0x1f,
+    PcieGen3Sff8639 = 0x20,
+    PcieMini52WithKeepouts = 0x21,
+    PcieMini52WithoutKeepouts = 0x22,
+    PcieMini76 = 0x23,
+    PcieGen4Sff8639 = 0x24,
     PcieGen5Sff8639 = 0x25,
-    PcieGen5 = 0xbf,
-    PcieGen5x1 = 0xc0,
-    PcieGen5x2 = 0xc1,
-    PcieGen5x4 = 0xc2,
-    PcieGen5x8 = 0xc3,
-    PcieGen5x16 = 0xc4,
+    OcpNic3Sff = 0x26,
+    OcpNic3Lff = 0x27,
+    OcpNicPrior3 = 0x28,
+    CxlFlexbus1 = 0x30,
+    Pc98C20 = 0xa0,
+    Pc98C24 = 0xa1,
+    Pc98E = 0xa2,
+    Pc98LocalBus = 0xa3,
+    Pc98Card = 0xa4,
+    Pcie = 0xa5,
+    Pciex1 = 0xa6,
+    Pciex2 = 0xa7,
+    Pciex4 = 0xa8,
+    Pciex8 = 0xa9,
+    Pciex16 = 0xaa,
+    PcieGen2 = 0xab,
+    PcieGen2x1 = 0xac,
+    PcieGen2x2 = 0xad,
+    PcieGen2x4 = 0xae,
+    PcieGen2x8 = 0xaf,
+    PcieGen2x16 = 0xb0,
+    PcieGen3 = 0xb1,
+    PcieGen3x1 = 0xb2,
+    PcieGen3x2 = 0xb3,
+    PcieGen3x4 = 0xb4,
+    PcieGen3x8 = 0xb5,
+    PcieGen3x16 = 0xb6,
+    PcieGen4 = 0xb8,
+    PcieGen4x1 = 0xb9,
+    PcieGen4x2 = 0xba,
+    PcieGen4x4 = 0xbb,
+    PcieGen4x8 = 0xbc,
+    PcieGen4x16 = 0xbd,
+    PcieGen5 = 0xbe,
+    PcieGen5x1 = 0xbf,
+    PcieGen5x2 = 0xc0,
+    PcieGen5x4 = 0xc1,
+    PcieGen5x8 = 0xc2,
+    PcieGen5x16 = 0xc3,
+    PcieGen6 = 0xc4, // and beyond
+    EdsffE1 = 0xc5,
+    EdsffE3 = 0xc6,
+}
+
+impl SlotType {
+    // All PCI Express slots, including the SFF-8639 (U.2) and PCI Express Mini variants but not
+    // form factors which happen to use PCI Express, such as M.2 and OCP NIC
+    pub fn is_pcie(&self) -> bool {
+        matches!(*self as u8, 0x1f..=0x25 | 0xa5..=0xc4)
+    }
 }
 
 #[repr(u8)]
@@ -255,6 +768,28 @@ pub enum SlotLength {
     FormFactor3_5Drive = 6,
 }
 
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default, AsBytes)]
+pub enum SlotHeight {
+    NotApplicable = 0,
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    FullHeight = 3,
+    LowProfile = 4,
+}
+
+// Slot pitch is the distance between the centers of adjacent slots, in 1/100 mm
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, AsBytes)]
+pub struct SlotPitch(byteorder::U16<LE>);
+
+impl SlotPitch {
+    pub fn from_hundredths_mm(n: u16) -> Self {
+        Self(n.into())
+    }
+}
+
 bitflags! {
     pub struct SlotCharacteristics1: u8 {
         const Unknown = 1 << 0;
@@ -276,16 +811,90 @@ bitflags! {
     }
 }
 
+// How the event log is accessed; values 0x80-0xff are OEM specific
 #[repr(u8)]
-#[derive(Copy, Clone, Debug, Default, AsBytes)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LogAccessMethod {
+    IndexedIo1x8Bit = 0,
+    IndexedIo2x8Bit = 1,
+    IndexedIo1x16Bit = 2,
+    MemoryMapped32Bit = 3,
+    GeneralPurposeNonVolatile = 4,
+}
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, Default)]
+    pub struct LogStatus: u8 {
+        const Valid = 1 << 0;
+        const Full = 1 << 1;
+    }
+}
+
+// Values 0x80-0xff are OEM specific
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum LogHeaderFormat {
+    #[default]
+    NoHeader = 0,
+    Type1 = 1,
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AsBytes)]
 pub enum ArrayLocation {
     Other = 1,
     #[default]
     Unknown = 2,
     SystemBoard = 3,
+    IsaAddonCard = 4,
+    EisaAddonCard = 5,
+    PciAddonCard = 6,
+    McaAddonCard = 7,
+    PcmciaAddonCard = 8,
+    ProprietaryAddonCard = 9,
+    NuBus = 0xa,
+    Pc98C20AddonCard = 0xa0,
+    Pc98C24AddonCard = 0xa1,
+    Pc98EAddonCard = 0xa2,
+    Pc98LocalBusAddonCard = 0xa3,
     CxlAddonCard = 0xa4,
 }
 
+// An array location value that the spec doesn't define
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnknownArrayLocation(pub u8);
+
+impl ArrayLocation {
+    pub fn from_u8(v: u8) -> Result<ArrayLocation, UnknownArrayLocation> {
+        use ArrayLocation::*;
+        Ok(match v {
+            1 => Other,
+            2 => Unknown,
+            3 => SystemBoard,
+            4 => IsaAddonCard,
+            5 => EisaAddonCard,
+            6 => PciAddonCard,
+            7 => McaAddonCard,
+            8 => PcmciaAddonCard,
+            9 => ProprietaryAddonCard,
+            0xa => NuBus,
+            0xa0 => Pc98C20AddonCard,
+            0xa1 => Pc98C24AddonCard,
+            0xa2 => Pc98EAddonCard,
+            0xa3 => Pc98LocalBusAddonCard,
+            0xa4 => CxlAddonCard,
+            v => return Err(UnknownArrayLocation(v)),
+        })
+    }
+
+    pub fn is_add_on_card(&self) -> bool {
+        !matches!(
+            self,
+            ArrayLocation::Other | ArrayLocation::Unknown | ArrayLocation::SystemBoard
+        )
+    }
+}
+
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, Default, AsBytes)]
 pub enum ArrayUse {
@@ -300,7 +909,7 @@ pub enum ArrayUse {
 }
 
 #[repr(u8)]
-#[derive(Copy, Clone, Debug, Default, AsBytes)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AsBytes)]
 pub enum ErrorCorrectionType {
     Other = 1,
     #[default]
@@ -312,8 +921,18 @@ pub enum ErrorCorrectionType {
     Crc = 7,
 }
 
+impl ErrorCorrectionType {
+    // Multi-bit ECC corrects single-bit errors too
+    pub fn corrects_single_bit(&self) -> bool {
+        matches!(
+            self,
+            ErrorCorrectionType::SingleBitEcc | ErrorCorrectionType::MultiBitEcc
+        )
+    }
+}
+
 #[repr(u8)]
-#[derive(Copy, Clone, Debug, Default, AsBytes)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AsBytes)]
 pub enum FormFactor {
     Other = 1,
     #[default]
@@ -332,6 +951,62 @@ pub enum FormFactor {
     Srimm = 14,
     FbDimm = 15,
     Die = 16,
+    Camm = 17,
+    Lpcamm2 = 18,
+}
+
+impl FormFactor {
+    // Removable memory modules, as opposed to chips or packages on the board
+    pub fn is_module(&self) -> bool {
+        use FormFactor::*;
+        matches!(
+            self,
+            Simm | Dimm | Rimm | Sodimm | Srimm | FbDimm | Camm | Lpcamm2
+        )
+    }
+}
+
+// Naming conventions for memory device locators generated from a (socket, channel, slot)
+// position, with all three numbered from 0
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LocatorFormat {
+    // "P0_D10" and "NODE 0 CHANNEL 1 DIMM 0"
+    #[default]
+    Generic,
+    // Channels as letters, as on many AMI-based boards: "CPU0_DIMM_B0" and
+    // "P0_Node0_Channel1_Dimm0"
+    ChannelLetters,
+}
+
+// A form factor value that the spec doesn't define
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnknownFormFactor(pub u8);
+
+impl FormFactor {
+    pub fn from_u8(v: u8) -> Result<FormFactor, UnknownFormFactor> {
+        use FormFactor::*;
+        Ok(match v {
+            1 => Other,
+            2 => Unknown,
+            3 => Simm,
+            4 => Sip,
+            5 => Chip,
+            6 => Dip,
+            7 => Zip,
+            8 => Proprietary,
+            9 => Dimm,
+            10 => Tsop,
+            11 => RowOfChips,
+            12 => Rimm,
+            13 => Sodimm,
+            14 => Srimm,
+            15 => FbDimm,
+            16 => Die,
+            17 => Camm,
+            18 => Lpcamm2,
+            v => return Err(UnknownFormFactor(v)),
+        })
+    }
 }
 
 #[repr(u8)]
@@ -371,6 +1046,86 @@ pub enum MemoryType {
     Ddr5 = 0x22,
     Lpddr5 = 0x23,
     Hbm3 = 0x24,
+    Gddr6 = 0x25,
+    Gddr6x = 0x26,
+    Lpddr5x = 0x27,
+}
+
+impl MemoryType {
+    pub fn from_u8(v: u8) -> MemoryTypeRaw {
+        MemoryTypeRaw(v)
+    }
+
+    // Standard DDR SDRAM, not including the LPDDR or GDDR variants
+    pub fn is_ddr(&self) -> bool {
+        use MemoryType::*;
+        matches!(self, Ddr | Ddr2 | Ddr2FbDimm | Ddr3 | Ddr4 | Ddr5)
+    }
+
+    pub fn is_lpddr(&self) -> bool {
+        use MemoryType::*;
+        matches!(self, Lpddr | Lpddr2 | Lpddr3 | Lpddr4 | Lpddr5 | Lpddr5x)
+    }
+
+    pub fn is_hbm(&self) -> bool {
+        matches!(self, MemoryType::Hbm | MemoryType::Hbm2 | MemoryType::Hbm3)
+    }
+}
+
+// A memory type value that may not be one the spec defines, e.g. from a parsed table
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AsBytes)]
+pub struct MemoryTypeRaw(pub u8);
+
+impl MemoryTypeRaw {
+    pub fn as_known(&self) -> Option<MemoryType> {
+        use MemoryType::*;
+        Some(match self.0 {
+            0x01 => Other,
+            0x02 => Unknown,
+            0x03 => Dram,
+            0x04 => Edram,
+            0x05 => Vram,
+            0x06 => Sram,
+            0x07 => Ram,
+            0x08 => Rom,
+            0x09 => Flash,
+            0x0a => Eeprom,
+            0x0b => Feprom,
+            0x0c => Eprom,
+            0x0d => Cdram,
+            0x0e => Dram3d,
+            0x0f => Sdram,
+            0x10 => Sgram,
+            0x11 => Rdram,
+            0x12 => Ddr,
+            0x13 => Ddr2,
+            0x14 => Ddr2FbDimm,
+            0x18 => Ddr3,
+            0x19 => Fbd2,
+            0x1a => Ddr4,
+            0x1b => Lpddr,
+            0x1c => Lpddr2,
+            0x1d => Lpddr3,
+            0x1e => Lpddr4,
+            0x1f => LogicalNonVolatile,
+            0x20 => Hbm,
+            0x21 => Hbm2,
+            0x22 => Ddr5,
+            0x23 => Lpddr5,
+            0x24 => Hbm3,
+            0x25 => Gddr6,
+            0x26 => Gddr6x,
+            0x27 => Lpddr5x,
+            _ => return None,
+        })
+    }
+}
+
+impl From<MemoryType> for MemoryTypeRaw {
+    fn from(t: MemoryType) -> Self {
+        MemoryTypeRaw(t as u8)
+    }
 }
 
 bitflags! {
@@ -393,8 +1148,37 @@ bitflags! {
     }
 }
 
+// Common combinations of type detail flags
+impl TypeDetail {
+    pub const RDIMM: Self = Self::Synchronous.union(Self::Registered);
+    pub const UDIMM: Self = Self::Synchronous.union(Self::Unbuffered);
+    // DDR5 modules are described no differently from any other generation
+    pub const DDR5_RDIMM: Self = Self::RDIMM;
+    pub const DDR5_UDIMM: Self = Self::UDIMM;
+    pub const LRDIMM: Self = Self::Synchronous.union(Self::Lrdimm);
+    // SO-DIMMs are always unbuffered
+    pub const SODIMM: Self = Self::Synchronous.union(Self::Unbuffered);
+    pub const NVDIMM: Self = Self::Synchronous.union(Self::NonVolatile);
+    // HBM stacks have no module, so there's no buffering to describe
+    pub const HBM: Self = Self::Synchronous;
+
+    // A best guess from the form factor alone. Full-size DIMMs may or may not be buffered, so
+    // they're only marked synchronous.
+    pub fn for_form_factor(ff: FormFactor) -> TypeDetail {
+        match ff {
+            FormFactor::Sodimm
+            | FormFactor::Camm
+            | FormFactor::Lpcamm2
+            | FormFactor::RowOfChips => Self::UDIMM,
+            FormFactor::Dimm | FormFactor::FbDimm => Self::Synchronous,
+            FormFactor::Die => Self::HBM,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 #[repr(u8)]
-#[derive(Copy, Clone, Debug, Default, AsBytes)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AsBytes)]
 pub enum MemoryTechnology {
     Other = 1,
     #[default]
@@ -404,6 +1188,46 @@ pub enum MemoryTechnology {
     NvdimmF = 5,
     NvdimmP = 6,
     IntelOptane = 7,
+    Mram = 8,
+    Hbm = 9,
+    Hbm2 = 10,
+}
+
+// A memory technology value that the spec doesn't define
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnknownMemoryTechnology(pub u8);
+
+impl MemoryTechnology {
+    pub fn from_u8(v: u8) -> Result<MemoryTechnology, UnknownMemoryTechnology> {
+        use MemoryTechnology::*;
+        Ok(match v {
+            1 => Other,
+            2 => Unknown,
+            3 => Dram,
+            4 => NvdimmN,
+            5 => NvdimmF,
+            6 => NvdimmP,
+            7 => IntelOptane,
+            8 => Mram,
+            9 => Hbm,
+            10 => Hbm2,
+            v => return Err(UnknownMemoryTechnology(v)),
+        })
+    }
+
+    pub fn is_persistent(&self) -> bool {
+        matches!(
+            self,
+            MemoryTechnology::NvdimmN
+                | MemoryTechnology::NvdimmF
+                | MemoryTechnology::NvdimmP
+                | MemoryTechnology::IntelOptane
+        )
+    }
+
+    pub fn is_volatile(&self) -> bool {
+        !self.is_persistent()
+    }
 }
 
 bitflags! {
@@ -416,14 +1240,163 @@ bitflags! {
     }
 }
 
+// The size of one of the Type 17 memory regions (non-volatile, volatile, cache or logical)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NvdimmSizeValue {
+    // The region isn't part of the device
+    NotApplicable,
+    Unknown,
+    // A size in bytes, which can't be 0 (`NotApplicable`) or u64::MAX (`Unknown`)
+    Bytes(u64),
+}
+
+// A `NvdimmSizeValue::Bytes` size which collides with one of the special values
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidNvdimmSize(pub u64);
+
+impl NvdimmSizeValue {
+    pub fn from_u64(v: u64) -> Self {
+        match v {
+            0 => Self::NotApplicable,
+            u64::MAX => Self::Unknown,
+            bytes => Self::Bytes(bytes),
+        }
+    }
+}
+
+impl TryFrom<NvdimmSizeValue> for u64 {
+    type Error = InvalidNvdimmSize;
+
+    fn try_from(v: NvdimmSizeValue) -> Result<u64, InvalidNvdimmSize> {
+        match v {
+            NvdimmSizeValue::NotApplicable => Ok(0),
+            NvdimmSizeValue::Unknown => Ok(u64::MAX),
+            NvdimmSizeValue::Bytes(bytes @ (0 | u64::MAX)) => Err(InvalidNvdimmSize(bytes)),
+            NvdimmSizeValue::Bytes(bytes) => Ok(bytes),
+        }
+    }
+}
+
+// A JEP-106 manufacturer ID as it appears in SPD: the number of continuation codes (with
+// parity) in the low byte, and the ID code (with parity) in the high byte. 0 means unknown.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct JedecManufacturerId(u16);
+
+impl JedecManufacturerId {
+    pub const UNKNOWN: Self = Self(0);
+
+    pub fn from_spd_bytes(continuation_codes: u8, id: u8) -> Self {
+        Self(u16::from_le_bytes([continuation_codes, id]))
+    }
+
+    // `bank` is the JEP-106 bank number, starting from 1, and `id` is the ID code in that bank
+    // including its parity bit. Bank n has n - 1 continuation codes.
+    pub fn new_checked(bank: u8, id: u8) -> Result<Self, JedecError> {
+        if !(1..=0x80).contains(&bank) {
+            return Err(JedecError::InvalidBank(bank));
+        }
+        if !has_odd_parity(id) {
+            return Err(JedecError::InvalidParity(id));
+        }
+        let continuation_codes = bank - 1;
+        let parity = if has_odd_parity(continuation_codes) {
+            0
+        } else {
+            0x80
+        };
+        Ok(Self::from_spd_bytes(continuation_codes | parity, id))
+    }
+
+    // Both bytes carry odd parity over bits 6:0 in bit 7
+    pub fn is_valid_parity(&self) -> bool {
+        self.0.to_le_bytes().into_iter().all(has_odd_parity)
+    }
+}
+
+fn has_odd_parity(b: u8) -> bool {
+    b.count_ones() % 2 == 1
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum JedecError {
+    // The byte doesn't have odd parity
+    InvalidParity(u8),
+    // Banks are numbered from 1, and there are at most 128 of them
+    InvalidBank(u8),
+}
+
+impl From<JedecManufacturerId> for u16 {
+    fn from(id: JedecManufacturerId) -> u16 {
+        id.0
+    }
+}
+
+// Position of a memory device within a row of an address partition; 0 is reserved
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AsBytes)]
+pub struct PartitionRowPosition(u8);
+
+impl PartitionRowPosition {
+    pub const UNKNOWN: Self = Self(0xff);
+
+    pub fn known(n: u8) -> Self {
+        assert!(n != 0 && n != 0xff);
+        Self(n)
+    }
+}
+
+impl Default for PartitionRowPosition {
+    fn default() -> Self {
+        Self::UNKNOWN
+    }
+}
+
+// Position of a memory device within an interleave; 0 means non-interleaved
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AsBytes)]
+pub struct InterleavePosition(u8);
+
+impl InterleavePosition {
+    pub const NON_INTERLEAVED: Self = Self(0);
+    pub const UNKNOWN: Self = Self(0xff);
+
+    pub fn from_u8(n: u8) -> Self {
+        Self(n)
+    }
+}
+
+// Maximum number of consecutive rows from a memory device accessed in a single interleaved
+// transfer; 0 means the device isn't part of an interleave
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AsBytes)]
+pub struct InterleaveDataDepth(u8);
+
+impl InterleaveDataDepth {
+    pub const NON_INTERLEAVED: Self = Self(0);
+    pub const UNKNOWN: Self = Self(0xff);
+
+    pub fn from_u8(n: u8) -> Self {
+        Self(n)
+    }
+}
+
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, Default, AsBytes)]
-pub enum PartitionRowPosition {
-    Reserved = 0,
+pub enum BatteryChemistry {
+    Other = 1,
     #[default]
-    Unknown = 0xff,
+    Unknown = 2,
+    LeadAcid = 3,
+    NickelCadmium = 4,
+    NickelMetalHydride = 5,
+    LithiumIon = 6,
+    ZincAir = 7,
+    LithiumPolymer = 8,
 }
 
+// The `PreviouslyRequestedImage`, `VendorSpecific` and `ProductSpecific` statuses carry extra
+// data, which is limited to `MAX_BOOT_STATUS_PAYLOAD` bytes.
 #[derive(Debug, Clone, Default)]
 pub enum BootStatus<'a> {
     #[default]
@@ -442,6 +1415,80 @@ pub enum BootStatus<'a> {
     ProductSpecific(u8, &'a [u8]),
 }
 
+// A `BootStatus` which owns its extra data, so it has no lifetime
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum OwnedBootStatus {
+    #[default]
+    NoErrorsDetected,
+    NoBootableMedia,
+    NormalOperatingSystemFailedToLoad,
+    FirmwareDetectedHardwareFailure,
+    OperatingSystemDetectedHardwareFailure,
+    UserRequestedBoot,
+    SystemSecurityViolation,
+    PreviouslyRequestedImage(Vec<u8>),
+    SystemWatchdogTimer,
+    VendorSpecific(u8, Vec<u8>),
+    ProductSpecific(u8, Vec<u8>),
+}
+
+impl OwnedBootStatus {
+    pub fn as_boot_status(&self) -> BootStatus<'_> {
+        match self {
+            Self::NoErrorsDetected => BootStatus::NoErrorsDetected,
+            Self::NoBootableMedia => BootStatus::NoBootableMedia,
+            Self::NormalOperatingSystemFailedToLoad => {
+                BootStatus::NormalOperatingSystemFailedToLoad
+            }
+            Self::FirmwareDetectedHardwareFailure => BootStatus::FirmwareDetectedHardwareFailure,
+            Self::OperatingSystemDetectedHardwareFailure => {
+                BootStatus::OperatingSystemDetectedHardwareFailure
+            }
+            Self::UserRequestedBoot => BootStatus::UserRequestedBoot,
+            Self::SystemSecurityViolation => BootStatus::SystemSecurityViolation,
+            Self::PreviouslyRequestedImage(extra) => BootStatus::PreviouslyRequestedImage(extra),
+            Self::SystemWatchdogTimer => BootStatus::SystemWatchdogTimer,
+            Self::VendorSpecific(code, extra) => BootStatus::VendorSpecific(*code, extra),
+            Self::ProductSpecific(code, extra) => BootStatus::ProductSpecific(*code, extra),
+        }
+    }
+}
+
+impl From<BootStatus<'_>> for OwnedBootStatus {
+    fn from(status: BootStatus) -> Self {
+        match status {
+            BootStatus::NoErrorsDetected => Self::NoErrorsDetected,
+            BootStatus::NoBootableMedia => Self::NoBootableMedia,
+            BootStatus::NormalOperatingSystemFailedToLoad => {
+                Self::NormalOperatingSystemFailedToLoad
+            }
+            BootStatus::FirmwareDetectedHardwareFailure => Self::FirmwareDetectedHardwareFailure,
+            BootStatus::OperatingSystemDetectedHardwareFailure => {
+                Self::OperatingSystemDetectedHardwareFailure
+            }
+            BootStatus::UserRequestedBoot => Self::UserRequestedBoot,
+            BootStatus::SystemSecurityViolation => Self::SystemSecurityViolation,
+            BootStatus::PreviouslyRequestedImage(extra) => {
+                Self::PreviouslyRequestedImage(extra.into())
+            }
+            BootStatus::SystemWatchdogTimer => Self::SystemWatchdogTimer,
+            BootStatus::VendorSpecific(code, extra) => Self::VendorSpecific(code, extra.into()),
+            BootStatus::ProductSpecific(code, extra) => Self::ProductSpecific(code, extra.into()),
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default, AsBytes)]
+pub enum MemoryChannelType {
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    RamBus = 3,
+    SyncLink = 4,
+}
+
+// Bits 0-1 and 6-63 are reserved and must be zero; there are no vendor-specific bits.
 bitflags! {
     pub struct TpmDeviceCharacteristics: u64 {
         const Unsupported = 1 << 2;
@@ -451,6 +1498,51 @@ bitflags! {
     }
 }
 
+impl TpmDeviceCharacteristics {
+    pub const NONE: Self = Self::empty();
+}
+
+// Values 0x80-0xff are BIOS vendor or OEM specific
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FirmwareVersionFormat {
+    #[default]
+    FreeForm = 0,
+    MajorMinor = 1,
+    Hex32 = 2,
+    Hex64 = 3,
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FirmwareIdFormat {
+    #[default]
+    FreeForm = 0,
+    UefiGuid = 1,
+}
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, Default)]
+    pub struct FirmwareInventoryCharacteristics: u16 {
+        const Updatable = 1 << 0;
+        const WriteProtected = 1 << 1;
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum FirmwareInventoryState {
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    Disabled = 3,
+    Enabled = 4,
+    Absent = 5,
+    StandbyOffline = 6,
+    StandbySpare = 7,
+    UnavailableOffline = 8,
+}
+
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, Default, AsBytes)]
 pub enum ProcessorArchitecture {
@@ -458,6 +1550,56 @@ pub enum ProcessorArchitecture {
     #[default]
     Riscv64 = 7,
     Riscv128 = 8,
+    LoongArch32 = 9,
+    LoongArch64 = 0xa,
+}
+
+// Revision of the processor-specific data in Type 44, with the major version in the high byte
+// and the minor version in the low byte
+#[repr(u16)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ProcessorSpecificRevision {
+    Rev1 = 0x0100,
+}
+
+// RISC-V and LoongArch number their revisions the same way
+pub type RiscvRevision = ProcessorSpecificRevision;
+pub type LoongArchRevision = ProcessorSpecificRevision;
+
+// The base ISA and the ISA extensions a LoongArch core supports
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, AsBytes)]
+pub struct LoongArchIsaFlags(u32);
+
+bitflags! {
+    impl LoongArchIsaFlags: u32 {
+        const La32Reduced = 1 << 0;
+        const La32 = 1 << 1;
+        const La64 = 1 << 2;
+        const Fp = 1 << 3;
+        const Lsx = 1 << 4;
+        const Lasx = 1 << 5;
+        const Complex = 1 << 6;
+        const Crypto = 1 << 7;
+        const Lvz = 1 << 8;
+        const LbtX86 = 1 << 9;
+        const LbtArm = 1 << 10;
+        const LbtMips = 1 << 11;
+    }
+}
+
+// The privilege levels a LoongArch core supports, PLV0 being the most privileged
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, AsBytes)]
+pub struct LoongArchPrivilegeLevels(u8);
+
+bitflags! {
+    impl LoongArchPrivilegeLevels: u8 {
+        const Plv0 = 1 << 0;
+        const Plv1 = 1 << 1;
+        const Plv2 = 1 << 2;
+        const Plv3 = 1 << 3;
+    }
 }
 
 #[repr(u8)]
@@ -469,3 +1611,33 @@ pub enum Xlen {
     Xlen64 = 2,
     Xlen128 = 3,
 }
+
+impl Xlen {
+    pub fn from_bits(bits: u8) -> Option<Xlen> {
+        match bits {
+            32 => Some(Xlen::Xlen32),
+            64 => Some(Xlen::Xlen64),
+            128 => Some(Xlen::Xlen128),
+            _ => None,
+        }
+    }
+
+    pub fn bits(&self) -> u8 {
+        match self {
+            Xlen::Unsupported => 0,
+            Xlen::Xlen32 => 32,
+            Xlen::Xlen64 => 64,
+            Xlen::Xlen128 => 128,
+        }
+    }
+
+    // The misa.MXL encoding happens to match the SMBIOS one
+    pub fn from_misa_mxl(mxl: u8) -> Option<Xlen> {
+        match mxl {
+            1 => Some(Xlen::Xlen32),
+            2 => Some(Xlen::Xlen64),
+            3 => Some(Xlen::Xlen128),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2024 Rivos, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Encodings shared by the voltage (Type 26), temperature (Type 28) and current (Type 29) probe
+//! structures.
+
+use zerocopy::{byteorder, byteorder::LE, AsBytes};
+
+// A probe reading or limit. The units depend on the probe type; 0x8000 means unknown.
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AsBytes)]
+pub struct ProbeReading(byteorder::U16<LE>);
+
+impl ProbeReading {
+    pub const UNKNOWN: Self = Self(byteorder::U16::from_bytes([0x00, 0x80]));
+
+    // Readings are signed, since temperatures can be negative. i16::MIN is the unknown value.
+    pub fn from_value(v: i16) -> Self {
+        Self((v as u16).into())
+    }
+
+    pub fn value(&self) -> Option<i16> {
+        if *self == Self::UNKNOWN {
+            None
+        } else {
+            Some(self.0.get() as i16)
+        }
+    }
+}
+
+impl Default for ProbeReading {
+    fn default() -> Self {
+        Self::UNKNOWN
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ProbeStatus {
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    Ok = 3,
+    NonCritical = 4,
+    Critical = 5,
+    NonRecoverable = 6,
+}
+
+// Voltage and current probes share the same locations
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ProbeLocation {
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    Processor = 3,
+    Disk = 4,
+    PeripheralBay = 5,
+    SystemManagementModule = 6,
+    Motherboard = 7,
+    MemoryModule = 8,
+    ProcessorModule = 9,
+    PowerUnit = 10,
+    AddInCard = 11,
+}
+
+impl From<ProbeLocation> for u8 {
+    fn from(location: ProbeLocation) -> u8 {
+        location as u8
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TemperatureProbeLocation {
+    Other = 1,
+    #[default]
+    Unknown = 2,
+    Processor = 3,
+    Disk = 4,
+    PeripheralBay = 5,
+    SystemManagementModule = 6,
+    Motherboard = 7,
+    MemoryModule = 8,
+    ProcessorModule = 9,
+    PowerUnit = 10,
+    AddInCard = 11,
+    FrontPanelBoard = 12,
+    BackPanelBoard = 13,
+    PowerSystemBoard = 14,
+    DriveBackPlane = 15,
+}
+
+impl From<TemperatureProbeLocation> for u8 {
+    fn from(location: TemperatureProbeLocation) -> u8 {
+        location as u8
+    }
+}
+
+// Bits 7:5 are the status, and bits 4:0 are the location
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AsBytes)]
+pub struct ProbeLocationAndStatus(u8);
+
+impl ProbeLocationAndStatus {
+    pub fn new(location: impl Into<u8>, status: ProbeStatus) -> Self {
+        let location = location.into();
+        assert!(location < 0x20);
+        Self(((status as u8) << 5) | location)
+    }
+
+    pub fn location(&self) -> u8 {
+        self.0 & 0x1f
+    }
+
+    pub fn status(&self) -> u8 {
+        self.0 >> 5
+    }
+}
+
+impl Default for ProbeLocationAndStatus {
+    fn default() -> Self {
+        Self::new(ProbeLocation::Unknown, ProbeStatus::Unknown)
+    }
+}
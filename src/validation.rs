@@ -0,0 +1,187 @@
+// Copyright 2024 Rivos, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Consistency checks across the structures in a table set, for things which can't be checked
+//! when a single structure is built.
+
+use crate::parser::SimpleStructureIterator;
+use crate::table_set::SmbiosTableSet;
+use crate::tables::{
+    voltage_range_is_valid, MemoryArrayMappedAddress, MemoryDevice, MemoryDeviceMappedAddress,
+};
+use crate::SmbiosStructure;
+use alloc::vec::Vec;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    // A Type 20 structure refers to a Type 19 handle which isn't in the set
+    MissingArrayMappedAddress {
+        device_mapped_address: u16,
+    },
+    // A Type 20 address range isn't contained within its Type 19 range
+    DeviceRangeOutsideArray {
+        device_mapped_address: u16,
+        array_mapped_address: u16,
+    },
+    // A Type 17 configured voltage isn't between its minimum and maximum voltages
+    VoltageRangeInvalid {
+        memory_device: u16,
+        min: u16,
+        max: u16,
+        configured: u16,
+    },
+}
+
+// Decode a Type 19/20 address range (in bytes) from the start of the structure body. The 32-bit
+// fields hold KiB, unless the starting address is all ones, in which case the extended fields at
+// `extended_offset` hold the range in bytes.
+fn address_range(body: &[u8], extended_offset: usize) -> (u64, u64) {
+    let dword = |i: usize| u64::from(u32::from_le_bytes(body[i..i + 4].try_into().unwrap()));
+    let qword = |i: usize| u64::from_le_bytes(body[i..i + 8].try_into().unwrap());
+    match dword(0) {
+        0xffff_ffff => (qword(extended_offset), qword(extended_offset + 8)),
+        starting => (starting << 10, (dword(4) << 10) | 0x3ff),
+    }
+}
+
+// Check that each memory device mapping lies within the memory array mapping it refers to
+pub fn validate_address_ranges(set: &SmbiosTableSet) -> Vec<ValidationError> {
+    let mut output = Vec::new();
+    set.serialize(&mut output);
+
+    let mut arrays = Vec::new();
+    let mut devices = Vec::new();
+    for header in SimpleStructureIterator::new(&output).flatten() {
+        match header.ty {
+            MemoryArrayMappedAddress::TYPE_NUMBER => {
+                arrays.push((header.handle, address_range(header.body, 11)))
+            }
+            MemoryDeviceMappedAddress::TYPE_NUMBER => {
+                let array_handle = u16::from_le_bytes([header.body[10], header.body[11]]);
+                devices.push((header.handle, array_handle, address_range(header.body, 15)));
+            }
+            _ => (),
+        }
+    }
+
+    let mut errors = Vec::new();
+    for (handle, array_handle, (starting, ending)) in devices {
+        match arrays.iter().find(|(h, _)| *h == array_handle) {
+            None => errors.push(ValidationError::MissingArrayMappedAddress {
+                device_mapped_address: handle,
+            }),
+            Some((_, (array_starting, array_ending))) => {
+                if starting < *array_starting || ending > *array_ending {
+                    errors.push(ValidationError::DeviceRangeOutsideArray {
+                        device_mapped_address: handle,
+                        array_mapped_address: array_handle,
+                    });
+                }
+            }
+        }
+    }
+    errors
+}
+
+// Check the voltages of each memory device, for structures that weren't built with
+// `MemoryDevice::set_voltage_range`
+pub fn validate_voltage_ranges(set: &SmbiosTableSet) -> Vec<ValidationError> {
+    let mut output = Vec::new();
+    set.serialize(&mut output);
+
+    let mut errors = Vec::new();
+    for header in SimpleStructureIterator::new(&output).flatten() {
+        // The voltages were added in 2.8, so older structures don't have them
+        if header.ty != MemoryDevice::TYPE_NUMBER || header.length < 0x28 {
+            continue;
+        }
+        let raw = header.raw();
+        let word = |i: usize| u16::from_le_bytes([raw[i], raw[i + 1]]);
+        let (min, max, configured) = (word(0x22), word(0x24), word(0x26));
+        if !voltage_range_is_valid(min, max, configured) {
+            errors.push(ValidationError::VoltageRangeInvalid {
+                memory_device: header.handle,
+                min,
+                max,
+                configured,
+            });
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_validate_address_ranges() {
+        let mut set = SmbiosTableSet::new();
+        let mut array = MemoryArrayMappedAddress::new(0x19);
+        array.set_address_range(0x8000_0000, 0x1_7fff_ffff);
+        set.add(array);
+
+        let mut inside = MemoryDeviceMappedAddress::new(0x20);
+        inside.set_address_range(0x8000_0000, 0xffff_ffff);
+        inside.set_memory_array_mapped_address_handle(0x19.into());
+        set.add(inside);
+
+        let mut outside = MemoryDeviceMappedAddress::new(0x21);
+        outside.set_address_range(0x1_0000_0000, 0x1_ffff_ffff);
+        outside.set_memory_array_mapped_address_handle(0x19.into());
+        set.add(outside);
+
+        let mut orphan = MemoryDeviceMappedAddress::new(0x22);
+        orphan.set_memory_array_mapped_address_handle(0x99.into());
+        set.add(orphan);
+
+        assert_eq!(
+            validate_address_ranges(&set),
+            [
+                ValidationError::DeviceRangeOutsideArray {
+                    device_mapped_address: 0x21,
+                    array_mapped_address: 0x19
+                },
+                ValidationError::MissingArrayMappedAddress {
+                    device_mapped_address: 0x22
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_voltage_ranges() {
+        let mut set = SmbiosTableSet::new();
+        let mut good = MemoryDevice::new(0x30);
+        good.set_voltage_range(1050, 1200, 1100).unwrap();
+        set.add(good);
+
+        let mut bad = MemoryDevice::new(0x31);
+        bad.set_minimum_voltage_mv(1100);
+        bad.set_maximum_voltage_mv(1200);
+        bad.set_configured_voltage_mv(1000);
+        set.add(bad);
+
+        // Unknown voltages aren't checked
+        set.add(MemoryDevice::new(0x32));
+
+        assert_eq!(
+            validate_voltage_ranges(&set),
+            [ValidationError::VoltageRangeInvalid {
+                memory_device: 0x31,
+                min: 1100,
+                max: 1200,
+                configured: 1000
+            }]
+        );
+
+        // Structures from before the voltages were added aren't checked either
+        let mut short = vec![17, 0x1b, 0x33, 0];
+        short.resize(0x1b, 0xff);
+        short.extend([0, 0]);
+        let parsed = SmbiosTableSet::from_raw_table_area(&short).unwrap();
+        assert!(validate_voltage_ranges(&parsed).is_empty());
+    }
+}